@@ -4,11 +4,20 @@ extern crate lazy_static;
 use colored::*;
 use pad::{Alignment, PadStr};
 use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, OnceCell};
 use std::char;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::env;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::io;
 use std::io::prelude::*;
+use std::process;
+use std::time::{Duration, Instant};
 
 // change str to char
 const BORDER_TLC: char = '┌';
@@ -19,14 +28,67 @@ const BORDER_BLC: char = '└';
 const BORDER_HORIZONTAL: char = '─';
 const BORDER_VERTICAL: char = '│';
 
-const NEWLINE: char = '\n';
+// Inner grid lines of `Board::render_as_large`
+const BORDER_TOP_TEE: char = '┬';
+const BORDER_BOTTOM_TEE: char = '┴';
+const BORDER_LEFT_TEE: char = '├';
+const BORDER_RIGHT_TEE: char = '┤';
+const BORDER_CROSS: char = '┼';
+
+// Size of one square printed by the `LARGE` command, in terminal rows and columns
+const LARGE_SQUARE_HEIGHT: usize = 3;
+const LARGE_SQUARE_WIDTH: usize = 5;
 
 const BOARD_EMPTY: char = ' ';
 const BOARD_MAN: char = '●';
 const BOARD_KING: char = '○';
 
+const WIN_SCORE: i32 = 1_000_000;
+
+// Upper bound on search length, keeps win scores distinguishable from material scores
+const MAX_SEARCH_PLIES: i32 = 1000;
+
+#[cfg(test)]
+const CRITICAL_SQUARES_MAX_DEPTH: usize = 4;
+
+// Search depth of the move feedback printed after every REPL move
+const MOVE_QUALITY_DEPTH: usize = 2;
+
+// Search depth and ply limit of both AIs in `SELFPLAY` games
+const SELF_PLAY_DEPTH: usize = 2;
+const SELF_PLAY_MAX_PLIES: usize = 200;
+
+// Score gain from passing the turn that marks position as zugzwang, half of a man
+const ZUGZWANG_MARGIN: i32 = 50;
+
+// Depth of `Game::zugzwang_check` run at the search root
+const ZUGZWANG_CHECK_DEPTH: usize = 2;
+
+// Plies the null move search is reduced by, on top of the passed move
+const NULL_MOVE_REDUCTION: usize = 2;
+
+// Game phase thresholds of 10x10 board, scaled by board area for other sizes,
+// see `Game::phase`
+const OPENING_MIN_PIECES: usize = 17;
+const ENDGAME_MAX_PIECES: usize = 8;
+
+// Smallest height and width of a board with the starting setup
+const MIN_BOARD_SIZE: usize = 4;
+
 const INTERNAL_ERROR_MESSAGE: &str = "Internal error, shouldn't get here. Oooops...";
 
+// Set to any value to log coordinate lookups to stderr in debug builds
+const TRACE_ENV_VAR: &str = "CHECKERS_TRACE";
+
+/// Whether `CHECKERS_TRACE` is set, read once and always false in release builds
+fn trace_enabled() -> bool {
+    lazy_static! {
+        static ref TRACE_ENABLED: bool = env::var(TRACE_ENV_VAR).is_ok();
+    }
+
+    cfg!(debug_assertions) && *TRACE_ENABLED
+}
+
 fn get_enemy(player: Player) -> Player {
     match player {
         Player::White => Player::Black,
@@ -42,7 +104,7 @@ fn get_tile_owner(tile: Tile) -> Option<Player> {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Tile {
     Empty,
     White,
@@ -51,6 +113,33 @@ enum Tile {
     WhiteKing,
 }
 
+impl Tile {
+    pub fn is_king(&self) -> bool {
+        matches!(self, Tile::WhiteKing | Tile::BlackKing)
+    }
+
+    /// Material worth of the pawn, man is 1 and king is 3.
+    pub fn piece_value(&self) -> i32 {
+        match self {
+            Tile::Empty => 0,
+            Tile::White | Tile::Black => 1,
+            Tile::WhiteKing | Tile::BlackKing => 3,
+        }
+    }
+
+    /// Same pawn kind owned by the other player.
+    #[cfg(test)]
+    pub fn with_swapped_color(&self) -> Tile {
+        match self {
+            Tile::Empty => Tile::Empty,
+            Tile::White => Tile::Black,
+            Tile::Black => Tile::White,
+            Tile::WhiteKing => Tile::BlackKing,
+            Tile::BlackKing => Tile::WhiteKing,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum Direction {
     NW,
@@ -59,32 +148,557 @@ enum Direction {
     SW,
 }
 
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [Direction::NW, Direction::NE, Direction::SE, Direction::SW]
+    }
+
+    /// Horizontal and vertical step of a single diagonal move
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::NW => (-1, -1),
+            Direction::NE => (1, -1),
+            Direction::SE => (1, 1),
+            Direction::SW => (-1, 1),
+        }
+    }
+}
+
+/// Special corners of the board, see `Board::corner_kind`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum CornerKind {
+    // Dark corner tile, the end of the longest diagonal
+    SingleCorner,
+    // One of the two dark tiles next to a light corner tile
+    DoubleCorner,
+}
+
+/// Known defensive structures in the back three rows, see `Board::detect_defense_formation`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum FormationName {
+    Triangle,
+    Diamond,
+    Pyramid,
+    Phalanx,
+    Dyke,
+}
+
+// Pawn offsets of every formation, relative to its frontmost left pawn,
+// positive dy points towards the back rank of the formation owner
+const FORMATIONS: &[(FormationName, &[(isize, isize)])] = &[
+    (FormationName::Triangle, &[(0, 0), (-1, 1), (1, 1)]),
+    (FormationName::Diamond, &[(0, 0), (-1, 1), (1, 1), (0, 2)]),
+    (
+        FormationName::Pyramid,
+        &[(0, 0), (-1, 1), (1, 1), (-2, 2), (0, 2), (2, 2)],
+    ),
+    (FormationName::Phalanx, &[(0, 0), (2, 0), (4, 0)]),
+    (FormationName::Dyke, &[(0, 0), (1, 1), (2, 2)]),
+    (FormationName::Dyke, &[(0, 0), (-1, 1), (-2, 2)]),
+];
+
+// Known opening positions of 10x10 board as (FEN prefix, suggested move, opening name),
+// see `Game::suggest_opening`. Moves in comments use international square numbers
+const OPENINGS: &[(&str, &str, &str)] = &[
+    // starting position, 32-28
+    (
+        "1b1b1b1b1b/b1b1b1b1b1/1b1b1b1b1b/b1b1b1b1b1/10/10/1w1w1w1w1w/w1w1w1w1w1/1w1w1w1w1w/w1w1w1w1w1 w",
+        "D7 E6",
+        "Standard",
+    ),
+    // 32-28, 18-23
+    (
+        "1b1b1b1b1b/b1b1b1b1b1/1b1b1b1b1b/b1b1b1b1b1/10/4w5/1w3w1w1w/w1w1w1w1w1/1w1w1w1w1w/w1w1w1w1w1 b",
+        "E4 F5",
+        "Center",
+    ),
+    // 32-28 18-23, 37-32
+    (
+        "1b1b1b1b1b/b1b1b1b1b1/1b1b1b1b1b/b1b3b1b1/5b4/4w5/1w3w1w1w/w1w1w1w1w1/1w1w1w1w1w/w1w1w1w1w1 w",
+        "C8 D7",
+        "Center Counter",
+    ),
+    // 33-28, 18-23
+    (
+        "1b1b1b1b1b/b1b1b1b1b1/1b1b1b1b1b/b1b1b1b1b1/10/4w5/1w1w3w1w/w1w1w1w1w1/1w1w1w1w1w/w1w1w1w1w1 b",
+        "E4 F5",
+        "Right Center",
+    ),
+    // 34-29, 19-23
+    (
+        "1b1b1b1b1b/b1b1b1b1b1/1b1b1b1b1b/b1b1b1b1b1/10/6w3/1w1w1w3w/w1w1w1w1w1/1w1w1w1w1w/w1w1w1w1w1 b",
+        "G4 F5",
+        "Flank",
+    ),
+];
+
+// Full moves during which the REPL prints opening suggestions
+const OPENING_SUGGESTION_TURNS: usize = 5;
+
 fn main() {
-    let mut game = Game::new();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (game, time_control) = match game_from_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            eprintln!(
+                "Usage: checkers [--fen <fen>] [--black-to-move] [--time <minutes>[+<increment>]]"
+            );
+            process::exit(1);
+        }
+    };
+
+    let mut session = Checkers::new(CheckersConfig {
+        white_player: prompt_player_name("white"),
+        black_player: prompt_player_name("black"),
+        time_control,
+        ..CheckersConfig::default()
+    });
+    session.start_new_game_from(game);
+    let game = session.current_game_mut();
+
+    game.board.debug = trace_enabled();
 
     game.draw_info();
+    println!(
+        "{} (White) vs {} (Black)",
+        game.white_player_name().unwrap_or("Human"),
+        game.black_player_name().unwrap_or("Human")
+    );
+
+    // Thinking time of the player to move is counted from here
+    let mut turn_started = Instant::now();
 
     loop {
+        if let Some(clock) = session.clock() {
+            println!("{}", clock);
+        }
+
+        let game = session.current_game_mut();
         game.draw_board();
 
+        if game.turn_number() <= OPENING_SUGGESTION_TURNS {
+            if let Some(suggestion) = game.suggest_opening() {
+                println!("{}", suggestion);
+            }
+        }
+
+        if let Some(reason) = game.can_draw_be_claimed() {
+            println!(
+                "Draw can be claimed: {}. Type CLAIM DRAW to take it.",
+                reason
+            );
+        }
+
         println!("{:?}", game.state);
         print!("Make move: ");
         io::stdout().flush().expect("IO error");
-        let mut move_description = String::new();
-        match io::stdin().read_line(&mut move_description) {
-            Ok(_) => (),
-            Err(e) => panic!("{}", e),
+        let move_description = match read_input(&mut io::stdin().lock()) {
+            Some(input) => input.to_uppercase(),
+            None => {
+                println!();
+                println!("Goodbye!");
+                break;
+            }
+        };
+
+        if handle_command(game, &move_description) {
+            continue;
         }
-        move_description = move_description.to_uppercase();
+
+        let before_move = game.clone();
         let success = game.make_move(&move_description);
+        let elapsed = turn_started.elapsed();
         println!("Moved? {:?}", success);
 
+        if let (Ok(()), Some(played_move)) = (&success, game.last_move()) {
+            println!(
+                "{}",
+                before_move.evaluate_move_quality(played_move, MOVE_QUALITY_DEPTH)
+            );
+        }
+
+        if let (Ok(()), GameState::Turn(player)) = (&success, before_move.state) {
+            session.record_move_time(player, elapsed);
+            turn_started = Instant::now();
+
+            if session
+                .clock()
+                .is_some_and(|clock| clock.is_flagged(player))
+            {
+                println!("{:?} ran out of time!", player);
+            }
+        }
+
+        let game = session.current_game();
         if game.state == GameState::Won(Player::White) {
             println!("White won!");
         } else if game.state == GameState::Won(Player::Black) {
             println!("Black won!");
+        } else if let GameState::Draw(reason) = game.state {
+            println!("Draw by {}!", reason);
+        }
+    }
+}
+
+/// Reads a single line of user input, `None` when the input has ended.
+fn read_input(reader: &mut dyn BufRead) -> Option<String> {
+    let mut input = String::new();
+
+    match reader.read_line(&mut input) {
+        Ok(0) => None,
+        Ok(_) => Some(input),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Asks for name of the `color` player, empty answer means `Human`.
+fn prompt_player_name(color: &str) -> String {
+    print!("Enter {} player name (or press Enter for 'Human'): ", color);
+    io::stdout().flush().expect("IO error");
+
+    let name = read_input(&mut io::stdin().lock()).unwrap_or_default();
+
+    match name.trim() {
+        "" => String::from("Human"),
+        name => name.to_string(),
+    }
+}
+
+/// Creates game and its time control from command line arguments:
+/// `--fen <fen>` starts from a position created by `Game::to_fen`,
+/// `--black-to-move` lets black make the first move,
+/// `--time <minutes>[+<increment seconds>]` plays with a clock.
+fn game_from_args(args: &[String]) -> Result<(Game, Option<TimeControl>), String> {
+    let mut game = Game::new();
+    let mut black_to_move = false;
+    let mut time_control = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fen" => {
+                let fen = args.next().ok_or("Missing value of --fen")?;
+                game = Game::from_fen(fen).map_err(|error| error.to_string())?;
+            }
+            "--black-to-move" => black_to_move = true,
+            "--time" => {
+                let description = args.next().ok_or("Missing value of --time")?;
+                time_control = Some(TimeControl::parse(description)?);
+            }
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if black_to_move && !game.is_game_over() {
+        game.state = GameState::Turn(Player::Black);
+        game.check_game_over();
+    }
+
+    Ok((game, time_control))
+}
+
+/// Answer of the `? <square>` command, legal destinations of the pawn on `square`.
+fn describe_square_moves(game: &Game, square: Option<&str>) -> String {
+    let player = match game.state {
+        GameState::Turn(player) => player,
+        _ => return String::from("The game has already ended"),
+    };
+    let index = match square.map(|square| game.board.parse_notation(square)) {
+        Some(Ok(index)) => index,
+        _ => return String::from("Usage: ? <square>, ex. ? C3"),
+    };
+    let notation = game.board.get_notation(index);
+
+    match game.board.get_tile_owner_at(index) {
+        Ok(Some(owner)) if owner == player => {
+            let destinations: Vec<String> = game
+                .destinations_for(index)
+                .into_iter()
+                .map(|target| game.board.get_notation(target))
+                .collect();
+
+            if destinations.is_empty() {
+                format!("Pawn on {} can't move", notation)
+            } else {
+                format!("{} can move to: {}", notation, destinations.join(", "))
+            }
+        }
+        Ok(Some(_)) => format!("{} is a pawn of the other player", notation),
+        _ => format!("{} is empty", notation),
+    }
+}
+
+/// Handles REPL commands other than moves.
+///
+/// Returns `false` if `input` is not a command and should be parsed as a move.
+fn handle_command(game: &mut Game, input: &str) -> bool {
+    let mut words = input.split_whitespace();
+
+    match words.next() {
+        // SOLVE <depth>: look for forced win of the player to move
+        Some("SOLVE") => {
+            let depth = match words.next().map(|word| word.parse::<usize>()) {
+                Some(Ok(depth)) => depth,
+                _ => {
+                    println!("Usage: SOLVE <depth>");
+                    return true;
+                }
+            };
+
+            let player = match game.state {
+                GameState::Turn(player) => player,
+                _ => {
+                    println!("The game has already ended");
+                    return true;
+                }
+            };
+
+            match game.get_forced_win_length(player, depth) {
+                Some(moves) => println!("{:?} wins in {} move(s)", player, moves),
+                None => println!("No forced win for {:?} within {} move(s)", player, depth),
+            }
+        }
+        // CHECK: look for a move that wins immediately
+        Some("CHECK") => match game.find_winning_move() {
+            Some(winning_move) => {
+                let separator = if winning_move.is_capture() { "x" } else { "-" };
+                println!(
+                    "Winning move: {} {} {}!",
+                    game.board.get_notation(winning_move.source()),
+                    separator,
+                    game.board.get_notation(winning_move.target())
+                );
+            }
+            None => println!("No immediate win found."),
+        },
+        // FORKS: list moves attacking two or more enemy pawns at once
+        Some("FORKS") => {
+            let forks: Vec<String> = game
+                .get_fork_moves()
+                .iter()
+                .map(|fork| {
+                    format!(
+                        "{} {}",
+                        game.board.get_notation(fork.source()),
+                        game.board.get_notation(fork.target())
+                    )
+                })
+                .collect();
+
+            if forks.is_empty() {
+                println!("No fork found.");
+            } else {
+                println!("Forks: {}", forks.join(", "));
+            }
+        }
+        // SAFE: list moves that don't hang a pawn, see `Game::moves_avoiding_recapture`
+        Some("SAFE") => {
+            let safe_moves: Vec<String> = game
+                .moves_avoiding_recapture()
+                .iter()
+                .map(|safe_move| game.board.get_move_notation(safe_move))
+                .collect();
+
+            if safe_moves.is_empty() {
+                println!("Every move hangs a pawn.");
+            } else {
+                println!("Safe moves: {}", safe_moves.join(", "));
+            }
+        }
+        // CHAINS <square>: draw capture chains of the pawn on the square as a tree
+        Some("CHAINS") => {
+            let source = match words.next().map(|word| game.board.parse_notation(word)) {
+                Some(Ok(index)) => index,
+                _ => {
+                    println!("Usage: CHAINS <square>, ex. CHAINS C3");
+                    return true;
+                }
+            };
+            // chains are generated from the perspective of the pawn owner
+            let source = match game.board.get_tile_owner_at(source) {
+                Ok(Some(Player::Black)) => game.board.reverse_index(&source),
+                _ => source,
+            };
+
+            match game.visualize_capture_chains(source) {
+                Ok(tree) => print!("{}", tree),
+                Err(error) => println!("{}", error),
+            }
+        }
+        // THREATS: list enemy pawns the player to move can capture
+        Some("THREATS") => {
+            let threatened: Vec<String> = game
+                .capturable_enemy_pieces()
+                .into_iter()
+                .map(|index| game.board.get_notation(index))
+                .collect();
+
+            if threatened.is_empty() {
+                println!("No enemy pawn can be captured.");
+            } else {
+                println!("Can capture: {}", threatened.join(", "));
+            }
+        }
+        // LINE <source> <target> ...: make several moves written on one line
+        Some("LINE") => {
+            let line: Vec<&str> = words.collect();
+
+            match game.apply_line(&line.join(" ")) {
+                Ok(()) => println!("Made {} move(s)", line.len() / 2),
+                Err(error) => println!("{}", error),
+            }
+        }
+        // LARGE: print the board with every square drawn as a box
+        Some("LARGE") => print!(
+            "{}",
+            game.board
+                .render_as_large(LARGE_SQUARE_HEIGHT, LARGE_SQUARE_WIDTH)
+        ),
+        // DIAGRAM: print the board as plain ASCII, for pasting into bug reports
+        Some("DIAGRAM") => print!("{}", game.board.to_diagram()),
+        // FLIP: print the board as seen from the black side
+        Some("FLIP") => println!("{}", game.board.get_drawed_board_for(Player::Black)),
+        // EVAL: show evaluation of the current position
+        Some("EVAL") => match game.state {
+            GameState::Turn(player) => game.print_evaluation_breakdown(player),
+            _ => println!("The game has already ended"),
+        },
+        // PDN: print the game so far in Portable Draughts Notation
+        Some("PDN") => println!("{}", game.to_pdn()),
+        // ? <square>: show where the pawn on the square can move
+        Some("?") => println!("{}", describe_square_moves(game, words.next())),
+        // CLAIM DRAW: end the game with a draw, see `Game::can_draw_be_claimed`
+        Some("CLAIM") if words.next() == Some("DRAW") => match game.claim_draw() {
+            Ok(()) => println!("Draw claimed"),
+            Err(error) => println!("{}", error),
+        },
+        // GOTO <n>: undo or redo moves until `n` plies were played
+        Some("GOTO") => {
+            let ply = match words.next().map(|word| word.parse::<usize>()) {
+                Some(Ok(ply)) => ply,
+                _ => {
+                    println!("Usage: GOTO <ply>");
+                    return true;
+                }
+            };
+
+            match game.goto_ply(ply) {
+                Ok(()) => println!("Jumped to ply {}", ply),
+                Err(error) => println!("{}", error),
+            }
+        }
+        // PERFT <depth>: count positions reachable at every depth up to `depth`
+        Some("PERFT") => {
+            let depth = match words.next().map(|word| word.parse::<usize>()) {
+                Some(Ok(depth)) => depth,
+                _ => {
+                    println!("Usage: PERFT <depth>");
+                    return true;
+                }
+            };
+
+            println!("{:<5} | Nodes", "Depth");
+            for (depth, nodes) in game.perft_breakdown(depth) {
+                println!("{:<5} | {}", depth, nodes);
+            }
+        }
+        // SELFPLAY <games>: time AI vs AI games from the starting position
+        Some("SELFPLAY") => {
+            let games = match words.next().map(|word| word.parse::<usize>()) {
+                Some(Ok(games)) => games,
+                _ => {
+                    println!("Usage: SELFPLAY <games>");
+                    return true;
+                }
+            };
+
+            let ai = AIConfig::new(SELF_PLAY_DEPTH);
+            let started = Instant::now();
+            let records = SelfPlay::new(ai.clone(), ai, SELF_PLAY_MAX_PLIES).run_batch(games);
+            for (i, record) in records.iter().enumerate() {
+                println!("Game {}: {:?}", i + 1, record.result);
+            }
+            println!(
+                "Played {} game(s) in {:.2?}",
+                records.len(),
+                started.elapsed()
+            );
+        }
+        // SELECT <square>: preview destinations of a pawn, then ask where to move it
+        Some("SELECT") => {
+            let player = match game.state {
+                GameState::Turn(player) => player,
+                _ => {
+                    println!("The game has already ended");
+                    return true;
+                }
+            };
+            let source = match words.next().map(|word| game.board.parse_notation(word)) {
+                Some(Ok(index)) => index,
+                _ => {
+                    println!("Usage: SELECT <square>, ex. SELECT C3");
+                    return true;
+                }
+            };
+            if game.board.get_tile_owner_at(source) != Ok(Some(player)) {
+                println!("{} is not your pawn", game.board.get_notation(source));
+                return true;
+            }
+
+            game.show_board_with_move_preview(source);
+            print!("Move to: ");
+            io::stdout().flush().expect("IO error");
+
+            let target = match read_input(&mut io::stdin().lock()) {
+                Some(target) => target.trim().to_uppercase(),
+                None => return true,
+            };
+            let description = format!("{} {}", game.board.get_notation(source), target);
+            println!("Moved? {:?}", game.make_move(&description));
+        }
+        // <square>: list moves of a pawn, then ask where to move it
+        Some(square) if words.next().is_none() && game.board.parse_notation(square).is_ok() => {
+            let moves = match game.get_moves_from_source(square) {
+                Ok(moves) if !moves.is_empty() => moves,
+                _ => {
+                    println!("No legal moves from {}", square);
+                    return true;
+                }
+            };
+
+            let alternatives: Vec<String> = moves
+                .iter()
+                .map(|available_move| {
+                    let kind = if available_move.is_capture() {
+                        "capture"
+                    } else {
+                        "move"
+                    };
+                    format!(
+                        "{} ({})",
+                        game.board.get_notation(available_move.target()),
+                        kind
+                    )
+                })
+                .collect();
+            println!("From {}: {}", square, alternatives.join(", "));
+            print!("Move to: ");
+            io::stdout().flush().expect("IO error");
+
+            let target = match read_input(&mut io::stdin().lock()) {
+                Some(target) => target.trim().to_uppercase(),
+                None => return true,
+            };
+            println!(
+                "Moved? {:?}",
+                game.make_move(&format!("{} {}", square, target))
+            );
         }
+        _ => return false,
     }
+
+    true
 }
 
 /// Checkers board representation.
@@ -109,23 +723,50 @@ fn main() {
 ///
 /// Methods `get_tile` and `set_tile` will pick correct board orientation based on player
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Board {
     height: usize,
     width: usize,
     tiles: Box<[Tile]>,
+    // Print index translations of black player perspective
+    debug: bool,
+}
+
+// `debug` doesn't change the position, so it is ignored
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.height == other.height && self.width == other.width && self.tiles == other.tiles
+    }
+}
+
+impl Eq for Board {}
+
+// has to agree with `PartialEq`, so boards can be transposition table keys
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.height.hash(state);
+        self.width.hash(state);
+        self.tiles.hash(state);
+    }
 }
 
 impl Board {
     /// Create board with height x width size.
+    ///
+    /// Panics on sizes rejected by `try_new`.
     fn new(height: usize, width: usize) -> Board {
-        // assert that board size is even vertically
-        assert_eq!(
-            height % 2,
-            0,
-            "Board vertical size cannot be odd! Got {}",
-            height
-        );
+        Board::try_new(height, width).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create board with height x width size and the starting setup.
+    ///
+    /// Height has to be even and the board at least `MIN_BOARD_SIZE` tiles in both
+    /// directions.
+    pub fn try_new(height: usize, width: usize) -> Result<Board, CheckersError> {
+        if height % 2 == 1 || height < MIN_BOARD_SIZE || width < MIN_BOARD_SIZE {
+            return Err(CheckersError::InvalidBoardSize { height, width });
+        }
+
         let mut tiles = vec![Tile::Empty; height * width];
 
         let top_tile = Tile::Black;
@@ -143,7 +784,7 @@ impl Board {
                  pawn  -> empty -> pawn  -> empty
                  pattern from top to bottom
                 */
-                if (x + y) % 2 == 1 {
+                if Index::new(x, y, Player::White).square_color().is_dark() {
                     tiles[x + y * width] = if y < height / 2 {
                         top_tile
                     } else {
@@ -153,11 +794,56 @@ impl Board {
             }
         }
 
-        Board {
+        Ok(Board {
             height,
             width,
             tiles: tiles.into_boxed_slice(),
+            debug: false,
+        })
+    }
+
+    /// Create board with height x width size without any pawns, any size is allowed.
+    #[cfg(test)]
+    pub fn new_empty(height: usize, width: usize) -> Board {
+        Board {
+            height,
+            width,
+            tiles: vec![Tile::Empty; height * width].into_boxed_slice(),
+            debug: false,
+        }
+    }
+
+    /// Create board with height x width size and only the given kings, placed by
+    /// algebraic notation, see `parse_notation`.
+    #[cfg(test)]
+    pub fn new_only_kings(
+        height: usize,
+        width: usize,
+        white_kings: &[&str],
+        black_kings: &[&str],
+    ) -> Result<Board, CheckersError> {
+        let mut board = Board::new_empty(height, width);
+
+        let kings = white_kings
+            .iter()
+            .map(|notation| (notation, Tile::WhiteKing))
+            .chain(
+                black_kings
+                    .iter()
+                    .map(|notation| (notation, Tile::BlackKing)),
+            );
+        for (notation, king) in kings {
+            let index = board.parse_notation(notation)?;
+            if !board.is_dark_square(index) {
+                return Err(CheckersError::InvalidNotation(
+                    "Kings have to stand on dark tiles",
+                ));
+            }
+
+            board.set_tile(index, king).expect(INTERNAL_ERROR_MESSAGE);
         }
+
+        Ok(board)
     }
 
     pub fn height(&self) -> usize {
@@ -180,10 +866,16 @@ impl Board {
     }
 
     pub fn get_tile<'a>(&self, index: Index) -> Result<Tile, &'a str> {
-        match index.orientation {
+        let tile = match index.orientation {
             Player::White => self.get_tile_white(index),
             Player::Black => self.get_tile_black(index),
+        };
+
+        if trace_enabled() {
+            eprintln!("[trace] get_tile({:?}) = {:?}", index, tile);
         }
+
+        tile
     }
 
     pub fn set_tile(&mut self, index: Index, tile: Tile) -> Result<(), &str> {
@@ -193,6 +885,18 @@ impl Board {
         }
     }
 
+    /// Get tile at (x, y) looking at board from white player perspective
+    #[cfg(test)]
+    pub fn get<'a>(&self, x: usize, y: usize) -> Result<Tile, &'a str> {
+        self.get_tile(Index::new(x, y, Player::White))
+    }
+
+    /// Set tile at (x, y) looking at board from white player perspective
+    #[cfg(test)]
+    pub fn place(&mut self, x: usize, y: usize, tile: Tile) -> Result<(), &str> {
+        self.set_tile(Index::new(x, y, Player::White), tile)
+    }
+
     /// Get title looking at board from white player perspective
     fn get_tile_white<'a>(&self, index: Index) -> Result<Tile, &'a str> {
         assert!(
@@ -201,7 +905,9 @@ impl Board {
         );
 
         if !self.validate_index(index) {
-            println!("Index outside of board");
+            if trace_enabled() {
+                eprintln!("[trace] index outside of board: {:?}", index);
+            }
             return Err("Index outside of board");
         }
 
@@ -216,7 +922,9 @@ impl Board {
         );
 
         if !self.validate_index(index) {
-            println!("Index outside of board");
+            if trace_enabled() {
+                eprintln!("[trace] index outside of board: {:?}", index);
+            }
             return Err("Index outside of board");
         }
 
@@ -233,12 +941,16 @@ impl Board {
         );
 
         if !self.validate_index(index) {
-            println!("Index outside of board");
+            if trace_enabled() {
+                eprintln!("[trace] index outside of board: {:?}", index);
+            }
             return Err("Index outside of board");
         }
 
         let reversed_index = self.reverse_index(&index);
-        println!("Index: {:?}, Reversed index: {:?}", index, reversed_index);
+        if self.debug {
+            println!("Index: {:?}, Reversed index: {:?}", index, reversed_index);
+        }
 
         self.get_tile_white(reversed_index)
     }
@@ -251,620 +963,6732 @@ impl Board {
         );
 
         if !self.validate_index(index) {
-            println!("Index outside of board");
+            if trace_enabled() {
+                eprintln!("[trace] index outside of board: {:?}", index);
+            }
             return Err("Index outside of board");
         }
 
         let reversed_index = self.reverse_index(&index);
-        println!("Index: {:?}, Reversed index: {:?}", index, reversed_index);
+        if self.debug {
+            println!("Index: {:?}, Reversed index: {:?}", index, reversed_index);
+        }
 
         self.set_tile_white(reversed_index, tile)
     }
 
-    fn validate_index(&self, index: Index) -> bool {
-        index.x < self.width && index.y < self.height
-    }
-
-    fn reverse_index(&self, index: &Index) -> Index {
-        let x = self.width() - index.x - 1;
-        let y = self.height() - index.y - 1;
+    /// Asserts in debug builds that all pawns stand on dark tiles and that no man
+    /// was left unpromoted on the last row.
+    pub fn check_invariants(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
 
-        Index::new(x, y, get_enemy(index.orientation))
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = self.tiles[x + y * self.width];
+                debug_assert!(
+                    tile == Tile::Empty || self.is_dark_square(Index::new(x, y, Player::White)),
+                    "{:?} on light tile ({}, {})",
+                    tile,
+                    x,
+                    y
+                );
+                debug_assert!(
+                    !(tile == Tile::White && y == 0 || tile == Tile::Black && y == self.height - 1),
+                    "Unpromoted {:?} on the last row ({}, {})",
+                    tile,
+                    x,
+                    y
+                );
+            }
+        }
     }
 
-    // TODO: add some kind of theme support, the board still looks meh in some terminals
-    pub fn get_drawed_board(&self) -> String {
-        // number of digits in vertical numeric notation
-        let vertical_index_digits = self.height.to_string().len();
-        // padding equal to vertical numeric notation width
-        let notation_padding = String::new().pad_to_width(vertical_index_digits);
+    /// Whether the tile is dark, pawns can stand on dark tiles only.
+    pub fn is_dark_square(&self, index: Index) -> bool {
+        self.to_white_index(index).square_color().is_dark()
+    }
 
-        // horizontal character notation
-        let horizontal_notation = (0..self.width)
-            .map(|i| char::from_u32((65 + i) as u32).expect("Unsupported width!"))
-            .collect::<String>();
+    pub fn has_pawns(&self, player: Player) -> bool {
+        self.tiles
+            .iter()
+            .any(|tile| get_tile_owner(*tile) == Some(player))
+    }
 
-        let horizontal_notation_line = format!(
-            "{} {} {}",
-            notation_padding, horizontal_notation, notation_padding
-        );
+    pub fn has_king(&self, player: Player) -> bool {
+        self.tiles
+            .iter()
+            .any(|tile| tile.is_king() && get_tile_owner(*tile) == Some(player))
+    }
 
-        let top_border = format!(
-            "{}{}{}",
-            BORDER_TLC,
-            (0..self.width())
-                .map(|_| BORDER_HORIZONTAL)
-                .collect::<String>(),
-            BORDER_TRC,
-        )
-        .blue()
-        .on_white()
-        .to_string();
-        let top_border_line = format!("{}{}{}", notation_padding, top_border, notation_padding);
+    /// Marks squares where each player could capture a pawn, in a single pass over the board.
+    #[cfg(test)]
+    pub fn compute_attack_map(&self) -> AttackMap {
+        self.compute_attack_map_with(false)
+    }
 
-        let bottom_border = format!(
-            "{}{}{}",
-            BORDER_BLC,
-            (0..self.width())
-                .map(|_| BORDER_HORIZONTAL)
-                .collect::<String>(),
-            BORDER_BRC,
-        )
-        .blue()
-        .on_white()
-        .to_string();
-        let bottom_border_line =
-            format!("{}{}{}", notation_padding, bottom_border, notation_padding);
+    /// Same as `compute_attack_map`, with kings attacking along the whole diagonal
+    /// when `flying_kings` is set, see `compute_flying_king_attacks`.
+    pub fn compute_attack_map_with(&self, flying_kings: bool) -> AttackMap {
+        let mut white_attacks = vec![false; self.height * self.width];
+        let mut black_attacks = vec![false; self.height * self.width];
 
-        let mut middle_rows: Vec<String> = Vec::new();
         for y in 0..self.height {
-            let mut middle_row = String::new();
-
-            let vertical_index = (y + 1)
-                .to_string()
-                .pad_to_width_with_alignment(vertical_index_digits, Alignment::Left);
-
-            middle_row.push_str(vertical_index.as_str());
-            middle_row.push_str(&BORDER_VERTICAL.to_string().blue().on_white().to_string());
-
-            let mut tile_row = String::new();
             for x in 0..self.width {
-                let tile = match self.get_tile(Index::new(x, y, Player::White)) {
-                    Ok(Tile::Empty) => self.get_empty_space(),
-                    Ok(Tile::White) => self.get_white_man(),
-                    Ok(Tile::Black) => self.get_black_man(),
-                    Ok(Tile::WhiteKing) => self.get_white_king(),
-                    Ok(Tile::BlackKing) => self.get_black_king(),
-                    Err(msg) => panic!(msg),
+                let tile = self.tiles[x + y * self.width];
+                let attacks = match get_tile_owner(tile) {
+                    Some(Player::White) => &mut white_attacks,
+                    Some(Player::Black) => &mut black_attacks,
+                    None => continue,
                 };
-                tile_row.push_str(&tile);
-            }
-            middle_row.push_str(&tile_row.on_blue().to_string());
-
-            middle_row.push_str(&BORDER_VERTICAL.to_string().blue().on_white().to_string());
-            middle_row.push_str(&vertical_index);
-            middle_row.push(NEWLINE);
-            middle_rows.push(middle_row);
-        }
-
-        [
-            horizontal_notation_line.clone(),
-            self.get_newline(),
-            top_border_line,
-            self.get_newline(),
-            middle_rows.concat(),
-            bottom_border_line,
-            self.get_newline(),
-            horizontal_notation_line,
-            self.get_newline(),
-        ]
-        .concat()
-    }
 
-    pub fn draw_info(&self) {
-        println!("Empty tile: {}", self.get_empty_space().on_blue());
-        println!("White man:  {}", self.get_white_man().on_blue());
-        println!("White king: {}", self.get_white_king().on_blue());
-        println!("Black man:  {}", self.get_black_man().on_blue());
-        println!("Black king: {}", self.get_black_king().on_blue());
-    }
+                // flying kings are handled separately, after all pawns
+                if flying_kings && tile.is_king() {
+                    continue;
+                }
 
-    fn get_white_man(&self) -> String {
-        BOARD_MAN.to_string().white().to_string()
-    }
+                let index = Index::new(x, y, Player::White);
+                for direction in Direction::all().iter() {
+                    let (dx, dy) = direction.offset();
 
-    fn get_black_man(&self) -> String {
-        BOARD_MAN.to_string().black().to_string()
-    }
+                    // pawn can be captured only if there is empty tile behind it
+                    let landing_index = match index.translate(2 * dx, 2 * dy) {
+                        Some(landing_index) if self.validate_index(landing_index) => landing_index,
+                        _ => continue,
+                    };
+                    if self.get_tile(landing_index) != Ok(Tile::Empty) {
+                        continue;
+                    }
 
-    fn get_white_king(&self) -> String {
-        BOARD_KING.to_string().white().to_string()
-    }
+                    // attacked tile is between pawn and landing tile, so it has to be on the board
+                    let attacked_index = index.translate(dx, dy).expect(INTERNAL_ERROR_MESSAGE);
+                    attacks[attacked_index.x + attacked_index.y * self.width] = true;
+                }
+            }
+        }
 
-    fn get_black_king(&self) -> String {
-        BOARD_KING.to_string().black().to_string()
-    }
+        if flying_kings {
+            for (player, attacks) in [
+                (Player::White, &mut white_attacks),
+                (Player::Black, &mut black_attacks),
+            ] {
+                for (king, squares) in self.compute_flying_king_attacks(player) {
+                    for square in squares {
+                        // pawn can be captured only if there is empty tile behind it
+                        let dx = (square.x as isize - king.x as isize).signum();
+                        let dy = (square.y as isize - king.y as isize).signum();
+                        let landing_empty = square
+                            .translate(dx, dy)
+                            .is_some_and(|landing| self.get_tile(landing) == Ok(Tile::Empty));
+
+                        if landing_empty {
+                            attacks[square.x + square.y * self.width] = true;
+                        }
+                    }
+                }
+            }
+        }
 
-    fn get_empty_space(&self) -> String {
-        BOARD_EMPTY.to_string()
+        AttackMap {
+            height: self.height,
+            width: self.width,
+            white_attacks: white_attacks.into_boxed_slice(),
+            black_attacks: black_attacks.into_boxed_slice(),
+        }
     }
 
-    fn get_newline(&self) -> String {
-        NEWLINE.to_string()
+    /// Tiles every `player` king could slide to or capture on as a flying king,
+    /// from white player perspective.
+    ///
+    /// Every diagonal ends on the first pawn, which is included if it can be captured.
+    pub fn compute_flying_king_attacks(&self, player: Player) -> Vec<(Index, Vec<Index>)> {
+        self.get_pieces(player)
+            .into_iter()
+            .filter(|index| self.get_tile(*index).is_ok_and(|tile| tile.is_king()))
+            .map(|king| {
+                let mut squares = Vec::new();
+
+                for direction in Direction::all().iter() {
+                    let (dx, dy) = direction.offset();
+                    let mut next = king.translate(dx, dy);
+
+                    while let Some(index) = next {
+                        match self.get_tile_owner_at(index) {
+                            Ok(None) => squares.push(index),
+                            Ok(Some(owner)) => {
+                                let can_capture = owner != player
+                                    && index.translate(dx, dy).is_some_and(|landing| {
+                                        self.get_tile(landing) == Ok(Tile::Empty)
+                                    });
+                                if can_capture {
+                                    squares.push(index);
+                                }
+                                break;
+                            }
+                            Err(_) => break,
+                        }
+                        next = index.translate(dx, dy);
+                    }
+                }
+
+                (king, squares)
+            })
+            .collect()
     }
-}
 
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.get_drawed_board())
-    }
-}
+    /// Representative of all boards symmetric to this one, so symmetric positions
+    /// compare equal.
+    ///
+    /// Symmetries have to keep pawns on dark tiles and men moving forward, so boards
+    /// of even width are rotated by 180 degrees with swapped pawn colors, while boards
+    /// of odd width are mirrored left-right. Rotated position is the same for the other
+    /// player to move, so the returned flag tells if the colors were swapped.
+    ///
+    /// Representative is the variant with the smallest sequence of tiles.
+    #[cfg(test)]
+    pub fn canonical(&self) -> (Board, bool) {
+        let swapped = self.width.is_multiple_of(2);
+        let symmetric = if swapped {
+            self.transformed(false, true)
+        } else {
+            self.transformed(true, false)
+        };
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum Player {
-    White,
-    Black,
-}
+        let tiles_key = |board: &Board| {
+            board
+                .tiles
+                .iter()
+                .map(|tile| *tile as u8)
+                .collect::<Vec<u8>>()
+        };
+        if tiles_key(&symmetric) < tiles_key(self) {
+            (symmetric, swapped)
+        } else {
+            (self.clone(), false)
+        }
+    }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum GameState {
-    Turn(Player),
-    Won(Player),
-}
+    /// Board mirrored left-right and/or rotated by 180 degrees with swapped colors.
+    #[cfg(test)]
+    fn transformed(&self, mirror: bool, rotate: bool) -> Board {
+        let mut board = self.clone();
 
-#[derive(Debug)]
-struct Game {
-    board: Board,
-    state: GameState,
-}
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut tile = self.tiles[x + y * self.width];
+                let (mut x_new, mut y_new) = (x, y);
+                if mirror {
+                    x_new = self.width - x_new - 1;
+                }
+                if rotate {
+                    x_new = self.width - x_new - 1;
+                    y_new = self.height - y_new - 1;
+                    tile = tile.with_swapped_color();
+                }
 
-impl Game {
-    pub fn new() -> Game {
-        Game {
-            board: Board::new(10, 10),
-            state: GameState::Turn(Player::White),
+                board.tiles[x_new + y_new * self.width] = tile;
+            }
         }
+
+        board
     }
+
+    /// Number of tiles of every kind:
+    /// `[empty, white men, white kings, black men, black kings]`.
     ///
-    /// Moves are using chess-like algebraic notation, without pawn descriptions.
-    /// We use pair of letter + numbers, delimited by single space:
-    /// `A6 B5` or `B1 C2`
+    /// Counts in a single pass over tiles, compute it once per position
+    /// when several counts are needed.
+    pub fn material_array(&self) -> [i32; 5] {
+        // `material_array` slot of every `Tile` variant, in declaration order
+        const SLOTS: [usize; 5] = [0, 1, 3, 4, 2];
+
+        let mut counts = [0; 5];
+        for tile in self.tiles.iter() {
+            counts[SLOTS[*tile as usize]] += 1;
+        }
+
+        counts
+    }
+
+    /// Material balance from white player perspective.
+    pub fn compute_material_score(&self, weights: &EvalWeights) -> i32 {
+        let [_, white_men, white_kings, black_men, black_kings] = self.material_array();
+
+        (white_men - black_men) * weights.man_value
+            + (white_kings - black_kings) * weights.king_value
+    }
+
+    /// Indices of all `player` pawns, from white player perspective.
+    pub fn get_pieces(&self, player: Player) -> Vec<Index> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| get_tile_owner(**tile) == Some(player))
+            .map(|(i, _)| Index::new(i % self.width, i / self.width, Player::White))
+            .collect()
+    }
+
+    /// Rewards `player` kings spread over the board rather than clustered together.
     ///
-    // todo: implement
-    pub fn make_move(&mut self, description: &str) -> Result<(), &str> {
-        // Check if the game is still in progress
-        if let GameState::Won(_) = self.state {
-            return Err("You can't make a move, the game has already ended");
-        };
+    /// Sum of king x and y coordinate variances, scaled by `king_spread_bonus`.
+    pub fn king_distribution_score(&self, player: Player, weights: &EvalWeights) -> i32 {
+        let kings: Vec<Index> = self
+            .get_pieces(player)
+            .into_iter()
+            .filter(|index| self.get_tile(*index).is_ok_and(|tile| tile.is_king()))
+            .collect();
 
-        // Try parsing move description
-        let board_move = match self.parse_move_description(description) {
-            Ok(m) => m,
-            Err(msg) => return Err(msg),
-        };
+        if kings.len() < 2 {
+            return 0;
+        }
 
-        // Check if the move is valid
-        let game_move = match self.check_move(board_move) {
-            Ok(game_move) => game_move,
-            Err(msg) => return Err(msg),
-        };
+        let variance = |coordinates: Vec<usize>| {
+            let count = coordinates.len() as f64;
+            let mean = coordinates.iter().sum::<usize>() as f64 / count;
 
-        // Get source pawn
-        let pawn = match self.board.get_tile(game_move.source()) {
-            Ok(tile) => tile,
-            Err(msg) => return Err(msg),
+            coordinates
+                .iter()
+                .map(|c| (*c as f64 - mean).powi(2))
+                .sum::<f64>()
+                / count
         };
 
-        // Remove pawn from source
-        self.board
-            .set_tile(board_move.source, Tile::Empty)
-            .expect(INTERNAL_ERROR_MESSAGE);
-        // Put pawn in target
-        self.board
-            .set_tile(board_move.target, pawn)
-            .expect(INTERNAL_ERROR_MESSAGE);
+        let spread = variance(kings.iter().map(|index| index.x).collect())
+            + variance(kings.iter().map(|index| index.y).collect());
 
-        // Remove captured pawns
-        match game_move.move_type {
-            MoveType::Move(_) | MoveType::KingMove(_) => (),
-            MoveType::Capture { captured_index, .. } => self
-                .board
-                .set_tile(captured_index, Tile::Empty)
-                .expect(INTERNAL_ERROR_MESSAGE),
-            _ => unimplemented!(),
-        }
+        (spread * weights.king_spread_bonus as f64).round() as i32
+    }
 
-        // change turn
-        self.change_turn();
+    /// Number of consecutive empty tiles from `index` in `direction`,
+    /// until a pawn or the board edge is reached.
+    ///
+    /// Direction is relative to the `index` orientation.
+    pub fn open_diagonal_length(&self, index: Index, direction: Direction) -> usize {
+        let (dx, dy) = direction.offset();
+
+        let mut length = 0;
+        let mut current = index;
+        while let Some(next) = current.translate(dx, dy) {
+            if !self.validate_index(next) || self.get_tile(next) != Ok(Tile::Empty) {
+                break;
+            }
 
-        Ok(())
+            length += 1;
+            current = next;
+        }
+
+        length
     }
 
-    fn change_turn(&mut self) {
-        match self.state {
-            GameState::Won(_) => panic!("The game has already ended!"),
-            GameState::Turn(player) => self.state = GameState::Turn(get_enemy(player)),
+    /// Classifies tiles of the single and double corners.
+    pub fn corner_kind(&self, index: Index) -> Option<CornerKind> {
+        if !self.validate_index(index) {
+            return None;
+        }
+
+        let index = self.to_white_index(index);
+        if index.square_color().is_light() {
+            return None;
+        }
+
+        let on_vertical_edge = index.x == 0 || index.x == self.width - 1;
+        let on_horizontal_edge = index.y == 0 || index.y == self.height - 1;
+        let next_to_vertical_edge = index.x == 1 || index.x + 2 == self.width;
+        let next_to_horizontal_edge = index.y == 1 || index.y + 2 == self.height;
+
+        if on_vertical_edge && on_horizontal_edge {
+            Some(CornerKind::SingleCorner)
+        } else if on_vertical_edge && next_to_horizontal_edge
+            || next_to_vertical_edge && on_horizontal_edge
+        {
+            // dark tile next to a corner means the corner itself is light
+            Some(CornerKind::DoubleCorner)
+        } else {
+            None
         }
     }
 
-    /// Parses move notation to an Index:
-    /// Ex. `A6 B5` or `B1 C2`.
+    /// Number of tiles from `start` to the board edge in `direction`, including `start`.
     ///
-    /// The move is indexed from white player perspective.
-    fn parse_move_description<'a>(&self, description: &str) -> Result<Move, &'a str> {
-        lazy_static! {
-            static ref ALGEBRAIC_NOTATION_REGEX: Regex =
-                Regex::new("([A-Z])([0-9]+) ([A-Z])([0-9]+)").unwrap();
+    /// Direction is relative to the `start` orientation.
+    #[cfg(test)]
+    pub fn diagonal_length(&self, start: Index, direction: Direction) -> usize {
+        if !self.validate_index(start) {
+            return 0;
         }
 
-        if !ALGEBRAIC_NOTATION_REGEX.is_match(description) {
-            return Err("Move does not match required notation");
-        }
+        let (dx, dy) = direction.offset();
+        let x_steps = if dx > 0 {
+            self.width - start.x - 1
+        } else {
+            start.x
+        };
+        let y_steps = if dy > 0 {
+            self.height - start.y - 1
+        } else {
+            start.y
+        };
 
-        // we can safely unwrap all of below, because regex is matching as per check above
-        let captures = ALGEBRAIC_NOTATION_REGEX
-            .captures_iter(description)
-            .nth(0)
-            .unwrap();
+        x_steps.min(y_steps) + 1
+    }
 
-        let source_letter: char = captures[1].chars().nth(0).unwrap();
-        let source_number: i32 = captures[2].parse().unwrap();
-        let target_letter: char = captures[3].chars().nth(0).unwrap();
-        let target_number: i32 = captures[4].parse().unwrap();
+    /// Length of the longest diagonal of dark tiles, 10 on 10x10 board.
+    #[cfg(test)]
+    pub fn max_diagonal_length(&self) -> usize {
+        let mut max_length = 0;
 
-        // horizontal indeces are created from A-Z letters
-        // we can use their char code value and subtract A value
-        // vertical indeces are created from 1-based numbers
-        // we only have to subtract 1
-        // TODO: try to refactor this code
-        let source_horizontal_index = match usize::try_from((source_letter as i32) - 65) {
-            Ok(number) => {
-                if number < self.board.height() {
-                    number
-                } else {
-                    return Err("Horizontal source index is outside the board");
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = Index::new(x, y, Player::White);
+                if index.square_color().is_light() {
+                    continue;
+                }
+
+                for direction in Direction::all().iter() {
+                    let length = self.diagonal_length(index, *direction);
+                    max_length = max_length.max(length);
                 }
             }
-            Err(_) => return Err("Horizontal source index is outside the board"),
+        }
+
+        max_length
+    }
+
+    /// Sum of open diagonal lengths around every `player` king, see `open_diagonal_length`.
+    pub fn king_mobility(&self, player: Player) -> usize {
+        self.get_pieces(player)
+            .into_iter()
+            .filter(|index| self.get_tile(*index).is_ok_and(|tile| tile.is_king()))
+            .map(|index| {
+                Direction::all()
+                    .iter()
+                    .map(|direction| self.open_diagonal_length(index, *direction))
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Heuristic guess whether `to_move` would rather pass in a kings only endgame.
+    ///
+    /// True when both players have only kings and `to_move` kings are less mobile than
+    /// the enemy kings. This is not a solver: it ignores captures, tempo and whether
+    /// any move actually worsens the position, so it gives both false positives and
+    /// false negatives. `Minimax` skips null move pruning where this holds.
+    pub fn is_likely_zugzwang(&self, to_move: Player) -> bool {
+        let enemy = get_enemy(to_move);
+        let only_kings = |player: Player| {
+            let pieces = self.get_pieces(player);
+            !pieces.is_empty()
+                && pieces
+                    .iter()
+                    .all(|index| self.get_tile(*index).is_ok_and(|tile| tile.is_king()))
         };
-        let source_vertical_index = match usize::try_from(source_number - 1) {
-            Ok(number) => {
-                if number < self.board.height() {
-                    number
-                } else {
-                    return Err("Vertical source index is outside the board");
+
+        only_kings(to_move)
+            && only_kings(enemy)
+            && self.king_mobility(to_move) < self.king_mobility(enemy)
+    }
+
+    /// Indices of pawns captured by the move, in capture order.
+    ///
+    /// Indices have the same orientation as the move source.
+    pub fn get_captured_indices(&self, available_move: &AvailableMove) -> Vec<Index> {
+        self.get_move_steps(available_move)
+            .into_iter()
+            .filter_map(|(_, _, captured)| captured)
+            .collect()
+    }
+
+    /// Single jumps making up the move, as `(source, target, captured)` accepted
+    /// by `apply_moves_batch`.
+    ///
+    /// Indices have the same orientation as the move source.
+    pub fn get_move_steps(
+        &self,
+        available_move: &AvailableMove,
+    ) -> Vec<(Index, Index, Option<Index>)> {
+        let source = available_move.source();
+
+        match available_move.move_type() {
+            MoveType::Move(target) | MoveType::KingMove(target) => vec![(source, *target, None)],
+            MoveType::Capture {
+                target_index,
+                captured_index,
+            }
+            | MoveType::KingCapture {
+                target_index,
+                captured_index,
+            } => vec![(source, *target_index, Some(*captured_index))],
+            MoveType::MultiCapture(path) | MoveType::KingMultiCapture(path) => {
+                let owner = self.get_tile_owner_at(source).ok().flatten();
+                let mut from = source;
+                let mut captured = Vec::new();
+                let mut steps = Vec::new();
+
+                // captured pawn is the enemy pawn between consecutive landing tiles,
+                // flying kings may cross tiles of pawns captured earlier in the chain
+                for to in path {
+                    let index = self.find_pawn_between(from, *to, owner, &captured);
+                    captured.extend(index);
+                    steps.push((from, *to, index));
+                    from = *to;
                 }
+
+                steps
             }
-            Err(_) => return Err("Vertical source index is outside the board"),
+        }
+    }
+
+    /// Board after applying `moves` one after another, as `(source, target, captured)`.
+    ///
+    /// Moves aren't checked for legality, only that every source holds a pawn.
+    /// All moves are applied to a single copy of the board.
+    pub fn apply_moves_batch(
+        &self,
+        moves: &[(Index, Index, Option<Index>)],
+    ) -> Result<Board, CheckersError> {
+        let mut board = self.clone();
+
+        for (source, target, captured) in moves {
+            board.apply_move(*source, *target, *captured)?;
+        }
+
+        Ok(board)
+    }
+
+    /// Moves pawn from `source` to `target` and removes `captured` pawn.
+    ///
+    /// Men reaching the last row are promoted.
+    fn apply_move(
+        &mut self,
+        source: Index,
+        target: Index,
+        captured: Option<Index>,
+    ) -> Result<(), CheckersError> {
+        let pawn = match self.get_tile(source) {
+            Ok(Tile::Empty) => return Err(CheckersError::EmptySource),
+            Ok(pawn) => pawn,
+            Err(_) => return Err(CheckersError::OutsideBoard),
         };
-        let target_horizontal_index = match usize::try_from((target_letter as i32) - 65) {
-            Ok(number) => {
-                if number < self.board.height() {
-                    number
-                } else {
-                    return Err("Horizontal target index is outside the board");
+
+        let target_row = self.to_white_index(target).y;
+        let pawn = match pawn {
+            Tile::White if target_row == 0 => Tile::WhiteKing,
+            Tile::Black if target_row == self.height - 1 => Tile::BlackKing,
+            pawn => pawn,
+        };
+
+        self.set_tile(source, Tile::Empty)
+            .map_err(|_| CheckersError::OutsideBoard)?;
+        self.set_tile(target, pawn)
+            .map_err(|_| CheckersError::OutsideBoard)?;
+        if let Some(captured) = captured {
+            self.set_tile(captured, Tile::Empty)
+                .map_err(|_| CheckersError::OutsideBoard)?;
+        }
+
+        Ok(())
+    }
+
+    /// First pawn not owned by `owner` on the diagonal between `from` and `to`,
+    /// pawns on `skip` tiles are ignored.
+    fn find_pawn_between(
+        &self,
+        from: Index,
+        to: Index,
+        owner: Option<Player>,
+        skip: &[Index],
+    ) -> Option<Index> {
+        let dx = (to.x as isize - from.x as isize).signum();
+        let dy = (to.y as isize - from.y as isize).signum();
+        let mut index = from.translate(dx, dy)?;
+
+        while index != to {
+            match self.get_tile_owner_at(index) {
+                Ok(Some(player)) if Some(player) != owner && !skip.contains(&index) => {
+                    return Some(index)
                 }
+                Ok(_) => index = index.translate(dx, dy)?,
+                Err(_) => return None,
             }
-            Err(_) => return Err("Horizontal target index is outside the board"),
-        };
-        let target_vertical_index = match usize::try_from(target_number - 1) {
-            Ok(number) => {
-                if number < self.board.height() {
-                    number
-                } else {
-                    return Err("Vertical target index is outside the board");
+        }
+
+        None
+    }
+
+    /// `player` pawns that the enemy could capture, from white player perspective.
+    #[cfg(test)]
+    pub fn get_pieces_under_attack(&self, player: Player) -> Vec<Index> {
+        self.get_pieces_under_attack_with(player, &self.compute_attack_map())
+    }
+
+    /// Same as `get_pieces_under_attack`, with attacks from an already computed
+    /// `attack_map`, see `Game::get_attack_map`.
+    pub fn get_pieces_under_attack_with(
+        &self,
+        player: Player,
+        attack_map: &AttackMap,
+    ) -> Vec<Index> {
+        self.get_pieces(player)
+            .into_iter()
+            .filter(|index| attack_map.is_attacked_by(*index, get_enemy(player)))
+            .collect()
+    }
+
+    /// Tiles where a pawn could be captured by `by_player`, from white player perspective.
+    #[cfg(test)]
+    pub fn get_threatened_squares(&self, by_player: Player) -> Vec<Index> {
+        self.get_threatened_squares_with(by_player, &self.compute_attack_map())
+    }
+
+    /// Same as `get_threatened_squares`, with attacks from an already computed `attack_map`.
+    pub fn get_threatened_squares_with(
+        &self,
+        by_player: Player,
+        attack_map: &AttackMap,
+    ) -> Vec<Index> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Index::new(x, y, Player::White)))
+            .filter(|index| attack_map.is_attacked_by(*index, by_player))
+            .collect()
+    }
+
+    /// Empty dark tiles where a `for_player` king couldn't be captured right away,
+    /// from white player perspective.
+    #[cfg(test)]
+    pub fn safe_king_squares(&self, for_player: Player) -> Vec<Index> {
+        self.safe_king_squares_with(for_player, &self.compute_attack_map())
+    }
+
+    /// Same as `safe_king_squares`, with attacks from an already computed `attack_map`.
+    pub fn safe_king_squares_with(&self, for_player: Player, attack_map: &AttackMap) -> Vec<Index> {
+        let threatened = self.get_threatened_squares_with(get_enemy(for_player), attack_map);
+
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Index::new(x, y, Player::White)))
+            .filter(|index| {
+                self.is_dark_square(*index)
+                    && self.get_tile(*index) == Ok(Tile::Empty)
+                    && !threatened.contains(index)
+            })
+            .collect()
+    }
+
+    /// Number of tiles next to `king_index` the king can step on without being captured
+    /// right away.
+    #[cfg(test)]
+    pub fn count_safe_king_moves(&self, king_index: Index, for_player: Player) -> usize {
+        self.count_safe_king_moves_with(king_index, for_player, &self.compute_attack_map())
+    }
+
+    /// Same as `count_safe_king_moves`, with attacks from an already computed `attack_map`.
+    pub fn count_safe_king_moves_with(
+        &self,
+        king_index: Index,
+        for_player: Player,
+        attack_map: &AttackMap,
+    ) -> usize {
+        let safe_squares = self.safe_king_squares_with(for_player, attack_map);
+        let king_index = self.to_white_index(king_index);
+
+        Direction::all()
+            .iter()
+            .filter_map(|direction| {
+                let (dx, dy) = direction.offset();
+                king_index.translate(dx, dy)
+            })
+            .filter(|target| safe_squares.contains(target))
+            .count()
+    }
+
+    /// `player` pawns without any friendly pawn on neighbouring diagonal tiles.
+    ///
+    /// Only diagonal neighbours can defend a pawn, as the other neighbours are light tiles.
+    pub fn get_isolated_pieces(&self, player: Player) -> Vec<Index> {
+        let pairs = self.get_adjacent_piece_pairs(player);
+
+        self.get_pieces(player)
+            .into_iter()
+            .filter(|index| !pairs.iter().any(|(a, b)| a == index || b == index))
+            .collect()
+    }
+
+    /// Pairs of diagonally neighbouring `player` pawns, from white player perspective.
+    ///
+    /// Every pair is reported once, smaller index first.
+    pub fn get_adjacent_piece_pairs(&self, player: Player) -> Vec<(Index, Index)> {
+        let mut pairs = Vec::new();
+
+        for index in self.get_pieces(player) {
+            // looking only down finds every pair once
+            for dx in [-1, 1].iter() {
+                match index.translate(*dx, 1) {
+                    Some(neighbour)
+                        if self.validate_index(neighbour)
+                            && self.get_tile_owner_at(neighbour) == Ok(Some(player)) =>
+                    {
+                        pairs.push((index.min(neighbour), index.max(neighbour)));
+                    }
+                    _ => (),
                 }
             }
-            Err(_) => return Err("Vertical target index is outside the board"),
-        };
+        }
 
-        let game_move = Move::new(
-            Index::new(
-                source_horizontal_index,
-                source_vertical_index,
-                Player::White,
-            ),
-            Index::new(
-                target_horizontal_index,
-                target_vertical_index,
-                Player::White,
-            ),
+        pairs
+    }
+
+    /// Number of `player` pawn pairs on the same row with a single empty tile between them.
+    ///
+    /// Bridge controls the tile between its pawns.
+    pub fn bridge_count(&self, player: Player) -> usize {
+        self.get_pieces(player)
+            .iter()
+            .filter(|index| {
+                // count every pair once, from its left pawn
+                let right = Index::new(index.x + 2, index.y, Player::White);
+                let between = Index::new(index.x + 1, index.y, Player::White);
+
+                self.validate_index(right)
+                    && self.get_tile_owner_at(right) == Ok(Some(player))
+                    && self.get_tile(between) == Ok(Tile::Empty)
+            })
+            .count()
+    }
+
+    /// Sum of rows advanced by `player` men from its back rank, kings are ignored.
+    pub fn compute_tempo(&self, player: Player) -> usize {
+        self.get_pieces(player)
+            .into_iter()
+            .filter(|index| self.get_tile(*index).is_ok_and(|tile| !tile.is_king()))
+            .map(|index| match player {
+                Player::White => self.height - index.y - 1,
+                Player::Black => index.y,
+            })
+            .sum()
+    }
+
+    /// Sum of `table` values of tiles with `player` pawns.
+    ///
+    /// `table` has a value for every tile, row by row from the `player` perspective,
+    /// so the same table works for both players.
+    #[cfg(test)]
+    pub fn piece_square_score(&self, player: Player, table: &[i32]) -> i32 {
+        assert_eq!(
+            table.len(),
+            self.height * self.width,
+            "Table needs a value for every tile! Got {} values",
+            table.len()
+        );
+
+        self.get_pieces(player)
+            .into_iter()
+            .map(|index| match player {
+                Player::White => index,
+                Player::Black => self.reverse_index(&index),
+            })
+            .map(|index| table[index.x + index.y * self.width])
+            .sum()
+    }
+
+    /// Checks if any `by_player` man can be promoted with its next step.
+    pub fn has_promotion_threat(&self, by_player: Player) -> bool {
+        self.count_promotion_threats(by_player) > 0
+    }
+
+    /// Number of `by_player` men one step from the last row, with a last row tile not taken
+    /// by a `by_player` piece. Enemy pieces don't block, they may be captured on the way.
+    pub fn count_promotion_threats(&self, by_player: Player) -> u8 {
+        let threats = self
+            .get_pieces(by_player)
+            .into_iter()
+            .filter(|index| self.get_tile(*index).is_ok_and(|tile| !tile.is_king()))
+            // look from the owner perspective, the last row is y == 0
+            .map(|index| match by_player {
+                Player::White => index,
+                Player::Black => self.reverse_index(&index),
+            })
+            .filter(|index| {
+                index.y == 1
+                    && [-1, 1].iter().any(|dx| {
+                        index
+                            .translate(*dx, -1)
+                            .and_then(|target| self.get_tile(target).ok())
+                            .is_some_and(|tile| get_tile_owner(tile) != Some(by_player))
+                    })
+            })
+            .count();
+
+        u8::try_from(threats).unwrap_or(u8::MAX)
+    }
+
+    /// Known formations of `player` pawns in its back three rows, each reported once.
+    pub fn detect_defense_formation(&self, player: Player) -> Vec<FormationName> {
+        let mut formations = Vec::new();
+        let back_rows = self.height.saturating_sub(3)..self.height;
+
+        for (name, offsets) in FORMATIONS {
+            if formations.contains(name) {
+                continue;
+            }
+
+            // formation is anchored on any pawn, looking from the owner perspective
+            let found = back_rows.clone().any(|y| {
+                (0..self.width).any(|x| {
+                    offsets.iter().all(|(dx, dy)| {
+                        match Index::new(x, y, player).translate(*dx, *dy) {
+                            Some(index) if back_rows.contains(&index.y) => {
+                                self.get_tile_owner_at(index) == Ok(Some(player))
+                            }
+                            _ => false,
+                        }
+                    })
+                })
+            });
+
+            if found {
+                formations.push(*name);
+            }
+        }
+
+        formations
+    }
+
+    /// Board in FEN-like notation, rows from top to bottom separated by `/`.
+    ///
+    /// `w` and `b` are white and black men, `W` and `B` are kings,
+    /// numbers count consecutive empty tiles. Ex. `1b1b/4/4/w1w1`.
+    pub fn to_fen(&self) -> String {
+        (0..self.height)
+            .map(|y| {
+                let mut row = String::new();
+                let mut empty_count = 0;
+
+                for x in 0..self.width {
+                    let symbol = match self.tiles[x + y * self.width] {
+                        Tile::Empty => {
+                            empty_count += 1;
+                            continue;
+                        }
+                        Tile::White => 'w',
+                        Tile::Black => 'b',
+                        Tile::WhiteKing => 'W',
+                        Tile::BlackKing => 'B',
+                    };
+
+                    if empty_count > 0 {
+                        row.push_str(&empty_count.to_string());
+                        empty_count = 0;
+                    }
+                    row.push(symbol);
+                }
+
+                if empty_count > 0 {
+                    row.push_str(&empty_count.to_string());
+                }
+
+                row
+            })
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    /// Creates board from FEN-like notation created by `Board::to_fen`.
+    pub fn from_fen(fen: &str) -> Result<Board, CheckersError> {
+        let mut tiles = Vec::new();
+        let mut width = None;
+
+        let rows: Vec<&str> = fen.split('/').collect();
+        for row in rows.iter() {
+            let mut row_tiles = Vec::new();
+            let mut empty_count: usize = 0;
+
+            for symbol in row.chars() {
+                if let Some(digit) = symbol.to_digit(10) {
+                    // no row is wider than 26 tiles, so longer runs can't be valid
+                    empty_count = empty_count
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit as usize))
+                        .filter(|count| *count <= 26)
+                        .ok_or(CheckersError::InvalidFen(
+                            "Board width has to be between 1 and 26",
+                        ))?;
+                    continue;
+                }
+
+                row_tiles.extend(vec![Tile::Empty; empty_count]);
+                empty_count = 0;
+
+                row_tiles.push(match symbol {
+                    'w' => Tile::White,
+                    'b' => Tile::Black,
+                    'W' => Tile::WhiteKing,
+                    'B' => Tile::BlackKing,
+                    _ => return Err(CheckersError::InvalidFen("Unknown tile symbol")),
+                });
+            }
+            row_tiles.extend(vec![Tile::Empty; empty_count]);
+
+            match width {
+                None => width = Some(row_tiles.len()),
+                Some(width) if width != row_tiles.len() => {
+                    return Err(CheckersError::InvalidFen("Rows have different widths"))
+                }
+                Some(_) => (),
+            }
+
+            tiles.extend(row_tiles);
+        }
+
+        // horizontal notation uses A-Z letters
+        let width = width.unwrap_or(0);
+        if width == 0 || width > 26 {
+            return Err(CheckersError::InvalidFen(
+                "Board width has to be between 1 and 26",
+            ));
+        }
+
+        Ok(Board {
+            height: rows.len(),
+            width,
+            tiles: tiles.into_boxed_slice(),
+            debug: false,
+        })
+    }
+
+    /// Compact binary board: height and width bytes followed by 3 bits per dark tile,
+    /// least significant bits first.
+    ///
+    /// Light tiles are skipped, pawns standing on them are lost. Fails for boards with a side
+    /// longer than 255 tiles, the header can't hold it.
+    #[cfg(test)]
+    pub fn serialize_to_bytes(&self) -> Result<Vec<u8>, CheckersError> {
+        if self.height > u8::MAX as usize || self.width > u8::MAX as usize {
+            return Err(CheckersError::BoardTooBigToSerialize {
+                height: self.height,
+                width: self.width,
+            });
+        }
+
+        let dark_tiles: Vec<Tile> = self
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                Index::new(i % self.width, i / self.width, Player::White)
+                    .square_color()
+                    .is_dark()
+            })
+            .map(|(_, tile)| *tile)
+            .collect();
+
+        let mut bytes = vec![0; 2 + (dark_tiles.len() * 3).div_ceil(8)];
+        bytes[0] = self.height as u8;
+        bytes[1] = self.width as u8;
+
+        for (i, tile) in dark_tiles.iter().enumerate() {
+            let code: u16 = match tile {
+                Tile::Empty => 0,
+                Tile::White => 1,
+                Tile::Black => 2,
+                Tile::WhiteKing => 3,
+                Tile::BlackKing => 4,
+            };
+
+            // 3 bits can span two bytes
+            let bit = i * 3;
+            let shifted = code << (bit % 8);
+            bytes[2 + bit / 8] |= shifted as u8;
+            if shifted > 0xFF {
+                bytes[2 + bit / 8 + 1] |= (shifted >> 8) as u8;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes board created by `Board::serialize_to_bytes`.
+    #[cfg(test)]
+    pub fn deserialize_from_bytes(bytes: &[u8]) -> Result<Board, CheckersError> {
+        if bytes.len() < 2 {
+            return Err(CheckersError::InvalidBytes("Missing board size"));
+        }
+
+        let height = bytes[0] as usize;
+        let width = bytes[1] as usize;
+        if height == 0 || width == 0 {
+            return Err(CheckersError::InvalidBytes("Board size can't be 0"));
+        }
+
+        let dark_tiles_count = (height * width) / 2;
+        if bytes.len() != 2 + (dark_tiles_count * 3).div_ceil(8) {
+            return Err(CheckersError::InvalidBytes("Wrong number of bytes"));
+        }
+
+        let mut tiles = vec![Tile::Empty; height * width];
+        let dark_indices = (0..height * width).filter(|i| {
+            Index::new(i % width, i / width, Player::White)
+                .square_color()
+                .is_dark()
+        });
+        for (i, tile_index) in dark_indices.enumerate() {
+            let bit = i * 3;
+            let low = bytes[2 + bit / 8] as u16;
+            let high = bytes.get(2 + bit / 8 + 1).copied().unwrap_or(0) as u16;
+            let code = ((low | high << 8) >> (bit % 8)) & 0b111;
+
+            tiles[tile_index] = match code {
+                0 => Tile::Empty,
+                1 => Tile::White,
+                2 => Tile::Black,
+                3 => Tile::WhiteKing,
+                4 => Tile::BlackKing,
+                _ => return Err(CheckersError::InvalidBytes("Unknown tile code")),
+            };
+        }
+
+        Ok(Board {
+            height,
+            width,
+            tiles: tiles.into_boxed_slice(),
+            debug: false,
+        })
+    }
+
+    /// Index looking at board from white player perspective.
+    pub fn to_white_index(&self, index: Index) -> Index {
+        match index.orientation {
+            Player::White => index,
+            Player::Black => self.reverse_index(&index),
+        }
+    }
+
+    /// Parses algebraic notation of a tile, inverse of `get_notation`.
+    ///
+    /// Index is from white player perspective.
+    pub fn parse_notation(&self, notation: &str) -> Result<Index, CheckersError> {
+        let mut chars = notation.chars();
+        let letter = chars
+            .next()
+            .filter(|letter| letter.is_ascii_uppercase())
+            .ok_or(CheckersError::InvalidNotation(
+                "Tile has to start with a letter",
+            ))?;
+        let number: usize = chars
+            .as_str()
+            .parse()
+            .map_err(|_| CheckersError::InvalidNotation("Tile has to end with a number"))?;
+
+        let index = Index::new(
+            (letter as u8 - b'A') as usize,
+            number.checked_sub(1).ok_or(CheckersError::OutsideBoard)?,
+            Player::White,
+        );
+        if !self.validate_index(index) {
+            return Err(CheckersError::OutsideBoard);
+        }
+
+        Ok(index)
+    }
+
+    /// Algebraic notation of a tile, ex. `A6`.
+    pub fn get_notation(&self, index: Index) -> String {
+        let index = self.to_white_index(index);
+        let letter = char::from_u32((65 + index.x) as u32).expect("Unsupported width!");
+
+        format!("{}{}", letter, index.y + 1)
+    }
+
+    /// Move notation accepted by `Game::make_move`, ex. `A6 B5`.
+    pub fn get_move_notation(&self, game_move: &AvailableMove) -> String {
+        format!(
+            "{} {}",
+            self.get_notation(game_move.source()),
+            self.get_notation(game_move.target())
+        )
+    }
+
+    fn validate_index(&self, index: Index) -> bool {
+        index.x < self.width && index.y < self.height
+    }
+
+    /// Number of `player` men in the starting position of `Board::new`.
+    pub fn initial_men_count(&self, player: Player) -> usize {
+        // two empty rows in the middle
+        let rows = match player {
+            Player::White => (self.height / 2 + 1)..self.height,
+            Player::Black => 0..(self.height / 2).saturating_sub(1),
+        };
+
+        rows.map(|y| self.dark_tiles_in_row(y).1).sum()
+    }
+
+    /// First dark tile x and number of dark tiles in row `y`, from white player perspective.
+    fn dark_tiles_in_row(&self, y: usize) -> (usize, usize) {
+        let first = (y + 1) % 2;
+
+        (first, (self.width + 1 - first) / 2)
+    }
+
+    fn reverse_index(&self, index: &Index) -> Index {
+        let x = self.width() - index.x - 1;
+        let y = self.height() - index.y - 1;
+
+        Index::new(x, y, get_enemy(index.orientation))
+    }
+
+    /// Board turned by 180 degrees, pawns keep their colors.
+    pub fn rotate_180(&self) -> Board {
+        let mut board = self.clone();
+        board.tiles.reverse();
+
+        board
+    }
+
+    /// Board as seen by `player`, black sees it rotated by 180 degrees.
+    pub fn rotated_for(&self, player: Player) -> Board {
+        match player {
+            Player::White => self.clone(),
+            Player::Black => self.rotate_180(),
+        }
+    }
+
+    /// Board drawn from `player` side, labels still name the unrotated tiles.
+    pub fn get_drawed_board_for(&self, player: Player) -> String {
+        let options = RenderOptions {
+            labels_reversed: player == Player::Black,
+            ..RenderOptions::default()
+        };
+
+        self.rotated_for(player).get_drawed_board_with(&options)
+    }
+
+    // TODO: add some kind of theme support, the board still looks meh in some terminals
+    pub fn get_drawed_board(&self) -> String {
+        self.get_drawed_board_with(&RenderOptions::default())
+    }
+
+    pub fn get_drawed_board_with(&self, options: &RenderOptions) -> String {
+        let mut buffer = Vec::new();
+        self.write_board_with(&mut buffer, options)
+            .expect("Writing to memory can't fail");
+
+        String::from_utf8(buffer).expect(INTERNAL_ERROR_MESSAGE)
+    }
+
+    /// Board with a sidebar listing pawns captured by each player.
+    ///
+    /// `white_captured` are pawns captured by white player, `black_captured` by black.
+    #[cfg(test)]
+    pub fn get_drawed_board_with_captures(
+        &self,
+        white_captured: &[Tile],
+        black_captured: &[Tile],
+    ) -> String {
+        let glyphs = |captured: &[Tile]| -> String {
+            captured
+                .iter()
+                .map(|tile| {
+                    let glyph = match tile {
+                        Tile::Empty => self.get_empty_space(1),
+                        Tile::White => self.get_white_man(1),
+                        Tile::Black => self.get_black_man(1),
+                        Tile::WhiteKing => self.get_white_king(1),
+                        Tile::BlackKing => self.get_black_king(1),
+                    };
+
+                    glyph.on_blue().to_string()
+                })
+                .collect()
+        };
+
+        let sidebar = [
+            String::from("Captured by white:"),
+            glyphs(white_captured),
+            String::new(),
+            String::from("Captured by black:"),
+            glyphs(black_captured),
+        ];
+
+        // sidebar starts next to the top border, so board lines keep their alignment
+        self.get_drawed_board()
+            .lines()
+            .enumerate()
+            .map(
+                |(i, line)| match i.checked_sub(1).and_then(|i| sidebar.get(i)) {
+                    Some(text) => format!("{}  {}\n", line, text),
+                    None => format!("{}\n", line),
+                },
+            )
+            .collect()
+    }
+
+    /// Board as HTML `<table>`, styled only by CSS classes.
+    ///
+    /// Every tile is a `<td>` with `tile` class, `dark` or `light` class and for occupied
+    /// tiles `man` or `king` and `white` or `black` classes, all prefixed with
+    /// `css_class_prefix`. Highlight classes are added without the prefix.
+    #[cfg(test)]
+    pub fn to_html_table(&self, options: &HtmlRenderOptions) -> String {
+        let prefix = &options.css_class_prefix;
+        let mut html = format!("<table class=\"{}board\">\n", prefix);
+
+        if options.show_coordinates {
+            html.push_str("<tr><th></th>");
+            for x in 0..self.width {
+                let letter = char::from_u32((65 + x) as u32).expect("Unsupported width!");
+                html.push_str(&format!("<th>{}</th>", letter));
+            }
+            html.push_str("</tr>\n");
+        }
+
+        for y in 0..self.height {
+            html.push_str("<tr>");
+            if options.show_coordinates {
+                html.push_str(&format!("<th>{}</th>", y + 1));
+            }
+
+            for x in 0..self.width {
+                let index = Index::new(x, y, Player::White);
+                let color = match index.square_color() {
+                    SquareColor::Dark => "dark",
+                    SquareColor::Light => "light",
+                };
+                let mut classes = vec![format!("{}tile", prefix), format!("{}{}", prefix, color)];
+
+                let tile = self.get_tile(index).expect(INTERNAL_ERROR_MESSAGE);
+                if let Some(owner) = get_tile_owner(tile) {
+                    let kind = if tile.is_king() { "king" } else { "man" };
+                    let player = match owner {
+                        Player::White => "white",
+                        Player::Black => "black",
+                    };
+                    classes.push(format!("{}{}", prefix, kind));
+                    classes.push(format!("{}{}", prefix, player));
+                }
+
+                for (highlighted, class) in &options.highlight_squares {
+                    if self.to_white_index(*highlighted) == index {
+                        classes.push(class.clone());
+                    }
+                }
+
+                html.push_str(&format!("<td class=\"{}\"></td>", classes.join(" ")));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+
+        html
+    }
+
+    /// Streams drawn board into `w`, without building the whole board in memory.
+    #[cfg(test)]
+    pub fn write_board(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.write_board_with(w, &RenderOptions::default())
+    }
+
+    pub fn write_board_with(&self, w: &mut dyn Write, options: &RenderOptions) -> io::Result<()> {
+        self.write_board_styled(w, options, |_, glyph| glyph.on_blue().to_string())
+    }
+
+    /// Plain ASCII board for bug reports and tests, without colors.
+    ///
+    /// Pawns use FEN letters, empty dark tiles are `.` and light tiles are blank.
+    pub fn to_diagram(&self) -> String {
+        let number_width = self.height.to_string().len();
+        let letters: Vec<String> = (0..self.width)
+            .map(|x| {
+                char::from_u32((65 + x) as u32)
+                    .expect("Unsupported width!")
+                    .to_string()
+            })
+            .collect();
+
+        let mut diagram = format!("{} {}\n", " ".repeat(number_width), letters.join(" "));
+        for y in 0..self.height {
+            let symbols: Vec<String> = (0..self.width)
+                .map(|x| {
+                    let index = Index::new(x, y, Player::White);
+                    let symbol = match self.get_tile(index) {
+                        Ok(Tile::White) => 'w',
+                        Ok(Tile::Black) => 'b',
+                        Ok(Tile::WhiteKing) => 'W',
+                        Ok(Tile::BlackKing) => 'B',
+                        _ if index.square_color().is_dark() => '.',
+                        _ => ' ',
+                    };
+
+                    symbol.to_string()
+                })
+                .collect();
+
+            diagram.push_str(&format!(
+                "{:>width$} {}\n",
+                y + 1,
+                symbols.join(" ").trim_end(),
+                width = number_width
+            ));
+        }
+
+        diagram
+    }
+
+    /// Board drawn with every tile as a box `square_height` rows high and `square_width`
+    /// columns wide, separated by grid lines. Pawns are centered in their tiles.
+    pub fn render_as_large(&self, square_height: usize, square_width: usize) -> String {
+        let grid_line = |left: char, middle: char, right: char| -> String {
+            let segment: String = (0..square_width).map(|_| BORDER_HORIZONTAL).collect();
+            let segments = vec![segment; self.width].join(&middle.to_string());
+
+            format!("{}{}{}\n", left, segments, right)
+        };
+
+        let mut large = grid_line(BORDER_TLC, BORDER_TOP_TEE, BORDER_TRC);
+        for y in 0..self.height {
+            for row in 0..square_height {
+                large.push(BORDER_VERTICAL);
+                for x in 0..self.width {
+                    let index = Index::new(x, y, Player::White);
+                    let square = match self.get_tile(index) {
+                        Ok(Tile::White) if row == square_height / 2 => {
+                            self.get_white_man(square_width)
+                        }
+                        Ok(Tile::Black) if row == square_height / 2 => {
+                            self.get_black_man(square_width)
+                        }
+                        Ok(Tile::WhiteKing) if row == square_height / 2 => {
+                            self.get_white_king(square_width)
+                        }
+                        Ok(Tile::BlackKing) if row == square_height / 2 => {
+                            self.get_black_king(square_width)
+                        }
+                        _ => self.get_empty_space(square_width),
+                    };
+
+                    if index.square_color().is_dark() {
+                        large.push_str(&square.on_blue().to_string());
+                    } else {
+                        large.push_str(&square);
+                    }
+                    large.push(BORDER_VERTICAL);
+                }
+                large.push('\n');
+            }
+
+            if y + 1 < self.height {
+                large.push_str(&grid_line(BORDER_LEFT_TEE, BORDER_CROSS, BORDER_RIGHT_TEE));
+            }
+        }
+        large.push_str(&grid_line(BORDER_BLC, BORDER_BOTTOM_TEE, BORDER_BRC));
+
+        large
+    }
+
+    /// Board with `source` pawn shown as selected and `targets` highlighted,
+    /// all other tiles are dimmed.
+    pub fn get_drawed_board_preview(&self, source: Index, targets: &[Index]) -> String {
+        let source = self.to_white_index(source);
+        let targets: Vec<Index> = targets
+            .iter()
+            .map(|target| self.to_white_index(*target))
+            .collect();
+
+        let mut buffer = Vec::new();
+        self.write_board_styled(&mut buffer, &RenderOptions::default(), |index, glyph| {
+            if index == source {
+                self.pad_glyph('→', 1).black().on_yellow().to_string()
+            } else if targets.contains(&index) {
+                glyph.on_green().to_string()
+            } else {
+                glyph.on_blue().dimmed().to_string()
+            }
+        })
+        .expect("Writing to memory can't fail");
+
+        String::from_utf8(buffer).expect(INTERNAL_ERROR_MESSAGE)
+    }
+
+    /// Writes board, every tile glyph is colored by `style` called with the tile
+    /// index from white player perspective.
+    fn write_board_styled<F>(
+        &self,
+        w: &mut dyn Write,
+        options: &RenderOptions,
+        style: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(Index, String) -> String,
+    {
+        let tile_width = options.tile_width;
+
+        // number of digits in vertical numeric notation
+        let vertical_index_digits = self.height.to_string().len();
+        // padding equal to vertical numeric notation width
+        let notation_padding = String::new().pad_to_width(vertical_index_digits);
+
+        // horizontal character notation
+        let horizontal_notation = (0..self.width)
+            .map(|i| {
+                let column = if options.labels_reversed {
+                    self.width - i - 1
+                } else {
+                    i
+                };
+                let letter = char::from_u32((65 + column) as u32).expect("Unsupported width!");
+                self.pad_glyph(letter, tile_width)
+            })
+            .collect::<String>();
+
+        let horizontal_notation_line = format!(
+            "{} {} {}",
+            notation_padding, horizontal_notation, notation_padding
+        );
+
+        let top_border = format!(
+            "{}{}{}",
+            BORDER_TLC,
+            (0..self.width() * tile_width)
+                .map(|_| BORDER_HORIZONTAL)
+                .collect::<String>(),
+            BORDER_TRC,
+        )
+        .blue()
+        .on_white();
+
+        let bottom_border = format!(
+            "{}{}{}",
+            BORDER_BLC,
+            (0..self.width() * tile_width)
+                .map(|_| BORDER_HORIZONTAL)
+                .collect::<String>(),
+            BORDER_BRC,
+        )
+        .blue()
+        .on_white();
+
+        let vertical_border = BORDER_VERTICAL.to_string().blue().on_white();
+
+        writeln!(w, "{}", horizontal_notation_line)?;
+        writeln!(w, "{}{}{}", notation_padding, top_border, notation_padding)?;
+
+        for y in 0..self.height {
+            let row = if options.labels_reversed {
+                self.height - y - 1
+            } else {
+                y
+            };
+            let row_number = if options.rows_bottom_up {
+                self.height - row
+            } else {
+                row + 1
+            };
+            let vertical_index = row_number
+                .to_string()
+                .pad_to_width_with_alignment(vertical_index_digits, Alignment::Left);
+
+            let mut tile_row = String::new();
+            for x in 0..self.width {
+                let index = Index::new(x, y, Player::White);
+                let tile = match self.get_tile(index) {
+                    Ok(Tile::Empty) => self.get_empty_space(tile_width),
+                    Ok(Tile::White) => self.get_white_man(tile_width),
+                    Ok(Tile::Black) => self.get_black_man(tile_width),
+                    Ok(Tile::WhiteKing) => self.get_white_king(tile_width),
+                    Ok(Tile::BlackKing) => self.get_black_king(tile_width),
+                    Err(msg) => panic!("{}", msg),
+                };
+                tile_row.push_str(&style(index, tile));
+            }
+
+            writeln!(
+                w,
+                "{}{}{}{}{}",
+                vertical_index, vertical_border, tile_row, vertical_border, vertical_index
+            )?;
+        }
+
+        writeln!(
+            w,
+            "{}{}{}",
+            notation_padding, bottom_border, notation_padding
+        )?;
+        writeln!(w, "{}", horizontal_notation_line)
+    }
+
+    pub fn draw_info(&self) {
+        print!("{}", self.info_string());
+    }
+
+    /// Legend of all tile kinds, one per line.
+    pub fn info_string(&self) -> String {
+        format!(
+            "Empty tile: {}\nWhite man:  {}\nWhite king: {}\nBlack man:  {}\nBlack king: {}\n",
+            self.get_empty_space(1).on_blue(),
+            self.get_white_man(1).on_blue(),
+            self.get_white_king(1).on_blue(),
+            self.get_black_man(1).on_blue(),
+            self.get_black_king(1).on_blue()
+        )
+    }
+
+    fn get_white_man(&self, tile_width: usize) -> String {
+        self.pad_glyph(BOARD_MAN, tile_width).white().to_string()
+    }
+
+    fn get_black_man(&self, tile_width: usize) -> String {
+        self.pad_glyph(BOARD_MAN, tile_width).black().to_string()
+    }
+
+    fn get_white_king(&self, tile_width: usize) -> String {
+        self.pad_glyph(BOARD_KING, tile_width).white().to_string()
+    }
+
+    fn get_black_king(&self, tile_width: usize) -> String {
+        self.pad_glyph(BOARD_KING, tile_width).black().to_string()
+    }
+
+    fn get_empty_space(&self, tile_width: usize) -> String {
+        self.pad_glyph(BOARD_EMPTY, tile_width)
+    }
+
+    /// Centers glyph in a cell `tile_width` terminal columns wide.
+    fn pad_glyph(&self, glyph: char, tile_width: usize) -> String {
+        glyph
+            .to_string()
+            .pad_to_width_with_alignment(tile_width, Alignment::Middle)
+    }
+}
+
+/// Squares attacked by each player, computed by `Board::compute_attack_map`.
+///
+/// Square is attacked when a pawn standing on it could be captured by the player.
+#[derive(Debug, Clone)]
+struct AttackMap {
+    height: usize,
+    width: usize,
+    white_attacks: Box<[bool]>,
+    black_attacks: Box<[bool]>,
+}
+
+impl AttackMap {
+    pub fn is_attacked_by(&self, index: Index, player: Player) -> bool {
+        if index.x >= self.width || index.y >= self.height {
+            return false;
+        }
+
+        // attacks are stored from white player perspective
+        let (x, y) = match index.orientation {
+            Player::White => (index.x, index.y),
+            Player::Black => (self.width - index.x - 1, self.height - index.y - 1),
+        };
+
+        match player {
+            Player::White => self.white_attacks[x + y * self.width],
+            Player::Black => self.black_attacks[x + y * self.width],
+        }
+    }
+}
+
+/// Board rendering options.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RenderOptions {
+    // Terminal columns taken by a single tile, 1 or 2
+    tile_width: usize,
+    // Print pawn legend in `Game::draw_info`
+    show_legend: bool,
+    // Number rows from the bottom, only row labels change
+    rows_bottom_up: bool,
+    // Label columns and rows right to left and bottom to top, for boards
+    // rotated by `Board::rotated_for`
+    labels_reversed: bool,
+}
+
+impl RenderOptions {
+    pub fn new(tile_width: usize) -> RenderOptions {
+        assert!(
+            tile_width == 1 || tile_width == 2,
+            "Tile width has to be 1 or 2! Got {}",
+            tile_width
+        );
+
+        RenderOptions {
+            tile_width,
+            show_legend: true,
+            rows_bottom_up: false,
+            labels_reversed: false,
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions::new(1)
+    }
+}
+
+/// `Board::to_html_table` options.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+struct HtmlRenderOptions {
+    // Prepended to every generated CSS class
+    css_class_prefix: String,
+    // Add header row with letters and header column with numbers
+    show_coordinates: bool,
+    // Extra CSS class of a tile
+    highlight_squares: Vec<(Index, String)>,
+}
+
+#[cfg(test)]
+impl Default for HtmlRenderOptions {
+    fn default() -> HtmlRenderOptions {
+        HtmlRenderOptions {
+            css_class_prefix: String::from("checkers-"),
+            show_coordinates: true,
+            highlight_squares: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_drawed_board())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+enum Player {
+    White,
+    Black,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum GamePhase {
+    Opening,
+    Midgame,
+    Endgame,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+enum GameState {
+    Turn(Player),
+    Won(Player),
+    Draw(DrawReason),
+}
+
+/// Rules variant options, defaults to standard checkers.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RulesConfig {
+    // Player left without legal moves draws instead of losing
+    no_moves_is_draw: bool,
+    // Captures are mandatory, only the ones taking the most pawns are legal
+    max_capture: bool,
+    // Break rule, among captures taking the most pawns only the ones taking
+    // the most kings are legal. Requires `max_capture`
+    max_kings_capture: bool,
+    // Men capture only towards the enemy side
+    men_capture_forward_only: bool,
+    // Kings move and capture along the whole diagonal
+    flying_kings: bool,
+    // Man reaching the last row during a capture continues capturing as a king,
+    // otherwise the capture ends there
+    king_continues_capture: bool,
+    // Promoting moves are mandatory, applied after capture rules
+    forced_promotion: bool,
+    // Order of generated captures, doesn't change which moves are legal
+    capture_ordering: CaptureOrdering,
+    // Plies without captures or man moves after which draw can be claimed
+    draw_ply_limit: usize,
+}
+
+impl Default for RulesConfig {
+    fn default() -> RulesConfig {
+        RulesConfig {
+            no_moves_is_draw: false,
+            max_capture: false,
+            max_kings_capture: false,
+            men_capture_forward_only: false,
+            flying_kings: false,
+            king_continues_capture: false,
+            forced_promotion: false,
+            capture_ordering: CaptureOrdering::default(),
+            // 40 moves of each player
+            draw_ply_limit: 80,
+        }
+    }
+}
+
+/// Order of captures in `Game::legal_moves`, for display only.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+enum CaptureOrdering {
+    /// Sorted by source then target square.
+    #[default]
+    BySource,
+    /// Most pawns captured first, ties sorted by source then target square.
+    ByCaptureCount,
+}
+
+impl RulesConfig {
+    /// Pool checkers, men capture only forward and kings are flying.
+    #[cfg(test)]
+    pub fn pool() -> RulesConfig {
+        RulesConfig {
+            men_capture_forward_only: true,
+            flying_kings: true,
+            ..RulesConfig::default()
+        }
+    }
+}
+
+/// Game details not affecting play.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct GameMetadata {
+    white_player: Option<String>,
+    black_player: Option<String>,
+}
+
+/// Board state overwritten by a move, see `Game::undo_move`.
+#[derive(Debug, Clone)]
+struct UndoRecord {
+    pawn: Tile,
+    captured: Vec<(Index, Tile)>,
+    state: GameState,
+}
+
+#[derive(Debug, Clone)]
+struct Game {
+    board: Board,
+    state: GameState,
+    rules: RulesConfig,
+    render_options: RenderOptions,
+    // Lazily computed for the current position, reset after every move
+    attack_map: OnceCell<AttackMap>,
+    // Applied moves, oldest first
+    history: Vec<AvailableMove>,
+    // What `undo_move` needs to restore, one record per `history` entry
+    undo_stack: Vec<UndoRecord>,
+    // Undone moves, most recently undone last
+    redo_stack: Vec<AvailableMove>,
+    // Branching factor of positions before every `make_move`
+    branching_factors: Vec<usize>,
+    // Full-move number, starts at 1 and grows after every black move
+    turn_number: usize,
+    // Tiles changed by the last applied or undone move, from white player perspective
+    dirty_squares: Vec<Index>,
+    metadata: GameMetadata,
+}
+
+impl Game {
+    pub fn new() -> Game {
+        Game::with_rules(RulesConfig::default())
+    }
+
+    pub fn with_rules(rules: RulesConfig) -> Game {
+        Game::with_board(Board::new(10, 10), rules)
+    }
+
+    /// Creates game from FEN created by `Game::to_fen`.
+    ///
+    /// Side to move after the board is optional, defaults to white. Positions rejected
+    /// by `validate_position` fail with the first error found.
+    pub fn from_fen(fen: &str) -> Result<Game, CheckersError> {
+        let mut parts = fen.split_whitespace();
+
+        let board = Board::from_fen(parts.next().unwrap_or(""))?;
+        let player = match parts.next() {
+            None | Some("w") => Player::White,
+            Some("b") => Player::Black,
+            Some(_) => return Err(CheckersError::InvalidFen("Unknown player to move")),
+        };
+        if parts.next().is_some() {
+            return Err(CheckersError::InvalidFen(
+                "Unexpected text after player to move",
+            ));
+        }
+
+        let mut game = Game::with_board(board, RulesConfig::default());
+        if let Some(error) = game.validate_position().first() {
+            return Err(CheckersError::InvalidPosition(*error));
+        }
+        game.state = GameState::Turn(player);
+        game.check_game_over();
+
+        Ok(game)
+    }
+
+    /// Board FEN followed by player to move, `w`, `b` or `-` when the game has ended.
+    pub fn to_fen(&self) -> String {
+        let player = match self.state {
+            GameState::Turn(Player::White) => "w",
+            GameState::Turn(Player::Black) => "b",
+            _ => "-",
+        };
+
+        format!("{} {}", self.board.to_fen(), player)
+    }
+
+    fn with_board(board: Board, rules: RulesConfig) -> Game {
+        Game {
+            board,
+            state: GameState::Turn(Player::White),
+            rules,
+            render_options: RenderOptions::default(),
+            attack_map: OnceCell::new(),
+            history: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            branching_factors: Vec::new(),
+            turn_number: 1,
+            dirty_squares: Vec::new(),
+            metadata: GameMetadata::default(),
+        }
+    }
+
+    pub fn set_player_names(&mut self, white: String, black: String) {
+        self.metadata.white_player = Some(white);
+        self.metadata.black_player = Some(black);
+    }
+
+    pub fn white_player_name(&self) -> Option<&str> {
+        self.metadata.white_player.as_deref()
+    }
+
+    pub fn black_player_name(&self) -> Option<&str> {
+        self.metadata.black_player.as_deref()
+    }
+
+    /// Attack map of the board, computed once and dropped whenever the board changes.
+    pub fn get_attack_map(&self) -> &AttackMap {
+        self.attack_map
+            .get_or_init(|| self.board.compute_attack_map_with(self.rules.flying_kings))
+    }
+
+    /// Result of the game so far, with number of plies played.
+    pub fn result(&self) -> GameResult {
+        let plies = self.history.len() as u32;
+
+        match self.state {
+            GameState::Turn(_) => GameResult::Ongoing,
+            GameState::Won(Player::White) => GameResult::WhiteWon(plies),
+            GameState::Won(Player::Black) => GameResult::BlackWon(plies),
+            GameState::Draw(reason) => GameResult::Draw(reason, plies),
+        }
+    }
+
+    /// Makes the best move found by `ai` for the player to move.
+    ///
+    /// Returns `None` when the game has already ended.
+    pub fn play_ai_move(&mut self, ai: &AIConfig) -> Option<AvailableMove> {
+        let (best_move, _) = ai.minimax().best_move(self)?;
+
+        self.branching_factors.push(self.branching_factor());
+        self.apply_move(&best_move);
+
+        Some(best_move)
+    }
+
+    /// Lets two AIs play until the game ends, or `max_plies` moves were made.
+    pub fn play_game_to_completion(
+        &mut self,
+        white_ai: &AIConfig,
+        black_ai: &AIConfig,
+        max_plies: usize,
+    ) -> GameResult {
+        for _ in 0..max_plies {
+            let ai = match self.state {
+                GameState::Turn(Player::White) => white_ai,
+                GameState::Turn(Player::Black) => black_ai,
+                _ => break,
+            };
+
+            self.play_ai_move(ai);
+        }
+
+        match self.result() {
+            GameResult::Ongoing => GameResult::MaxPliesReached,
+            result => result,
+        }
+    }
+
+    /// Exports finished or ongoing game as a structured record.
+    ///
+    /// Event details aren't tracked by `Game`, so they are left empty.
+    pub fn to_game_record(&self) -> GameRecord {
+        let result = self.result();
+
+        let moves = self
+            .history
+            .iter()
+            .map(|game_move| MoveRecord {
+                move_notation: self.board.get_move_notation(game_move),
+                time_ms: None,
+                evaluation: None,
+            })
+            .collect();
+
+        GameRecord {
+            white_player: self.metadata.white_player.clone(),
+            black_player: self.metadata.black_player.clone(),
+            date: None,
+            event: None,
+            result,
+            moves,
+        }
+    }
+
+    /// Replays recorded moves on a new game.
+    #[cfg(test)]
+    pub fn from_game_record(record: GameRecord) -> Result<Game, CheckersError> {
+        let moves: Vec<&str> = record
+            .moves
+            .iter()
+            .map(|move_record| move_record.move_notation.as_str())
+            .collect();
+        let mut game = Game::from_move_list(&moves, RulesConfig::default())?;
+
+        game.metadata.white_player = record.white_player;
+        game.metadata.black_player = record.black_player;
+
+        Ok(game)
+    }
+
+    /// State of the game after `moves`, without changing this game.
+    ///
+    /// Moves are indexed from the perspective of the player making them, as in `check_move`.
+    #[cfg(test)]
+    pub fn peek(&self, moves: &[Move]) -> Result<GameState, CheckersError> {
+        let mut game = self.clone();
+
+        for game_move in moves {
+            let available_move = game.check_move(game_move.clone())?;
+            game.apply_move(&available_move);
+        }
+
+        Ok(game.state)
+    }
+
+    /// Replays moves in `make_move` notation on a new game with `rules`.
+    ///
+    /// Failed move is reported as `CheckersError::InvalidMove`, numbered from 1.
+    #[cfg(test)]
+    pub fn from_move_list(moves: &[&str], rules: RulesConfig) -> Result<Game, CheckersError> {
+        let mut game = Game::with_rules(rules);
+
+        for (i, move_description) in moves.iter().enumerate() {
+            game.make_move(move_description)
+                .map_err(|error| CheckersError::InvalidMove {
+                    move_number: i + 1,
+                    reason: error.to_string(),
+                })?;
+        }
+
+        Ok(game)
+    }
+
+    /// FEN of the starting position and of the position after every move, the last
+    /// one is the current position.
+    ///
+    /// Positions are recreated by undoing and redoing the history on a copy of the game.
+    #[cfg(test)]
+    pub fn export_to_fen_sequence(&self) -> Vec<String> {
+        let mut game = self.clone();
+        game.undo_to(0).expect(INTERNAL_ERROR_MESSAGE);
+
+        let mut fens = vec![game.to_fen()];
+        for _ in 0..self.half_move_number() {
+            game.redo_move();
+            fens.push(game.to_fen());
+        }
+
+        fens
+    }
+
+    /// Makes consecutive moves written on one line as source and target pairs,
+    /// ex. `A3 B4 C5 D6`.
+    ///
+    /// Stops on the first move that can't be made, moves before it stay made.
+    pub fn apply_line(&mut self, line: &str) -> Result<(), CheckersError> {
+        let line = line.to_uppercase();
+        let tiles: Vec<&str> = line.split_whitespace().collect();
+
+        for (i, pair) in tiles.chunks(2).enumerate() {
+            let invalid_move = |reason: String| CheckersError::InvalidMove {
+                move_number: i + 1,
+                reason,
+            };
+
+            if pair.len() < 2 {
+                return Err(invalid_move(String::from("Missing target tile")));
+            }
+            self.make_move(&pair.join(" "))
+                .map_err(|error| invalid_move(error.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Result in PDN notation, `1-0`, `0-1`, `1/2-1/2` or `*` for ongoing game.
+    pub fn result_string(&self) -> &'static str {
+        match self.state {
+            GameState::Turn(_) => "*",
+            GameState::Won(Player::White) => "1-0",
+            GameState::Won(Player::Black) => "0-1",
+            GameState::Draw(_) => "1/2-1/2",
+        }
+    }
+
+    /// Exports game in Portable Draughts Notation, with player names and result tags.
+    pub fn to_pdn(&self) -> String {
+        let result = self.result_string();
+
+        let mut pdn = format!(
+            "[White \"{}\"]\n[Black \"{}\"]\n[Result \"{}\"]\n\n",
+            self.white_player_name().unwrap_or("?"),
+            self.black_player_name().unwrap_or("?"),
+            result
+        );
+
+        // history may start with a black move, so count turns back from the current one
+        let black_moves = self
+            .undo_stack
+            .iter()
+            .filter(|record| record.state == GameState::Turn(Player::Black))
+            .count();
+        let mut turn_number = self.turn_number - black_moves;
+
+        for (i, (game_move, record)) in self.history.iter().zip(&self.undo_stack).enumerate() {
+            match record.state {
+                GameState::Turn(Player::White) => pdn.push_str(&format!("{}. ", turn_number)),
+                _ if i == 0 => pdn.push_str(&format!("{}... ", turn_number)),
+                _ => (),
+            }
+            if record.state == GameState::Turn(Player::Black) {
+                turn_number += 1;
+            }
+
+            let separator = if game_move.is_capture() { "x" } else { "-" };
+            pdn.push_str(&format!(
+                "{}{}{} ",
+                self.board.get_notation(game_move.source()),
+                separator,
+                self.board.get_notation(game_move.target())
+            ));
+        }
+        pdn.push_str(result);
+
+        pdn
+    }
+
+    /// Number of legal moves of the player to move, measures position complexity.
+    pub fn branching_factor(&self) -> usize {
+        self.legal_moves().len()
+    }
+
+    /// Average branching factor of positions where moves were made with `make_move`.
+    #[cfg(test)]
+    pub fn average_branching_factor(&self) -> Option<f64> {
+        if self.branching_factors.is_empty() {
+            return None;
+        }
+
+        let total: usize = self.branching_factors.iter().sum();
+
+        Some(total as f64 / self.branching_factors.len() as f64)
+    }
+
+    /// Most recently applied move, `None` before the first move.
+    pub fn last_move(&self) -> Option<&AvailableMove> {
+        self.history.last()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        matches!(self.state, GameState::Won(_) | GameState::Draw(_))
+    }
+
+    /// Classifies position by number of pawns and kings left on the board.
+    ///
+    /// On 10x10 board more than 16 pawns is opening and fewer than 9 is endgame. The opening
+    /// lasts until the first king appears, the endgame starts early when kings outnumber men.
+    pub fn phase(&self) -> GamePhase {
+        let [_, white_men, white_kings, black_men, black_kings] = self.board.material_array();
+        let kings = white_kings + black_kings;
+        let pieces = (white_men + white_kings + black_men + black_kings) as usize;
+
+        if kings > white_men + black_men {
+            return GamePhase::Endgame;
+        }
+
+        // compare pieces per 100 tiles, to scale thresholds with board size
+        let area = self.board.height() * self.board.width();
+        if pieces * 100 <= ENDGAME_MAX_PIECES * area {
+            GamePhase::Endgame
+        } else if pieces * 100 >= OPENING_MIN_PIECES * area && kings == 0 {
+            GamePhase::Opening
+        } else {
+            GamePhase::Midgame
+        }
+    }
+
+    /// Quick sanity check of pawn counts, see `Game::validate_position`.
+    #[cfg(test)]
+    pub fn is_position_reachable(&self) -> bool {
+        self.validate_position().is_empty()
+    }
+
+    /// Pawn counts that can't be reached from the starting position.
+    ///
+    /// Every player can have at most as many pawns as at the start, and every king
+    /// had to be promoted from a man that is no longer on the board.
+    pub fn validate_position(&self) -> Vec<PositionError> {
+        let [_, white_men, white_kings, black_men, black_kings] = self.board.material_array();
+        let mut errors = Vec::new();
+
+        for (player, men, kings) in [
+            (Player::White, white_men, white_kings),
+            (Player::Black, black_men, black_kings),
+        ] {
+            let initial_men = self.board.initial_men_count(player) as i32;
+
+            if men + kings > initial_men {
+                errors.push(PositionError::TooManyPieces(player));
+            }
+            if kings > (initial_men - men).max(0) {
+                errors.push(PositionError::TooManyKings(player));
+            }
+        }
+
+        for player in [Player::White, Player::Black] {
+            for index in self.board.get_pieces(player) {
+                if !self.board.is_dark_square(index) {
+                    errors.push(PositionError::PieceOnLightSquare(index));
+                }
+            }
+        }
+
+        // men promote on reaching the last row, so they can't stand there
+        for (y, man) in [(0, Tile::White), (self.board.height() - 1, Tile::Black)] {
+            for x in 0..self.board.width() {
+                let index = Index::new(x, y, Player::White);
+                if self.board.get_tile(index) == Ok(man) {
+                    errors.push(PositionError::UnpromotedMan(index));
+                }
+            }
+        }
+
+        errors
+    }
+    ///
+    /// Moves are using chess-like algebraic notation, without pawn descriptions.
+    /// We use pair of letter + numbers, delimited by single space:
+    /// `A6 B5` or `B1 C2`
+    ///
+    // todo: implement
+    pub fn make_move(&mut self, description: &str) -> Result<(), CheckersError> {
+        // Check if the game is still in progress
+        if self.is_game_over() {
+            return Err(CheckersError::GameOver);
+        };
+
+        // Try parsing move description
+        let board_move = self.parse_move_description(description)?;
+
+        // Check if the move is valid
+        let game_move = self.check_move(board_move)?;
+
+        self.branching_factors.push(self.branching_factor());
+        self.apply_move(&game_move);
+
+        Ok(())
+    }
+
+    /// Applies move that was already checked and passes the turn.
+    fn apply_move(&mut self, game_move: &AvailableMove) {
+        // Collect captured pawns before the board changes, so capture paths
+        // crossing the source tile or each other are resolved consistently
+        let captured = self.board.get_captured_indices(game_move);
+
+        // Get source pawn
+        let pawn = self
+            .board
+            .get_tile(game_move.source())
+            .expect(INTERNAL_ERROR_MESSAGE);
+
+        // Remember overwritten tiles for undo
+        self.undo_stack.push(UndoRecord {
+            pawn,
+            captured: captured
+                .iter()
+                .map(|index| {
+                    (
+                        *index,
+                        self.board.get_tile(*index).expect(INTERNAL_ERROR_MESSAGE),
+                    )
+                })
+                .collect(),
+            state: self.state,
+        });
+        self.redo_stack.clear();
+
+        // Remove pawn from source
+        self.board
+            .set_tile(game_move.source(), Tile::Empty)
+            .expect(INTERNAL_ERROR_MESSAGE);
+        // Put pawn in target, men reaching the last row are promoted,
+        // even if a capture continued from there
+        let promoted = game_move.path().iter().any(|index| index.y == 0);
+        let pawn = match pawn {
+            Tile::White if promoted => Tile::WhiteKing,
+            Tile::Black if promoted => Tile::BlackKing,
+            pawn => pawn,
+        };
+        self.board
+            .set_tile(game_move.target(), pawn)
+            .expect(INTERNAL_ERROR_MESSAGE);
+
+        self.dirty_squares = self.changed_squares(game_move, &captured);
+
+        // Remove captured pawns all at once
+        for index in captured {
+            self.board
+                .set_tile(index, Tile::Empty)
+                .expect(INTERNAL_ERROR_MESSAGE);
+        }
+        self.board.check_invariants();
+
+        // board has changed, so the attack map is stale
+        self.attack_map.take();
+
+        self.history.push(game_move.clone());
+
+        if self.state == GameState::Turn(Player::Black) {
+            self.turn_number += 1;
+        }
+
+        // change turn
+        self.change_turn();
+
+        // end the game if next player can't move
+        self.check_game_over();
+    }
+
+    /// Number of plies played so far.
+    pub fn half_move_number(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Tiles changed by the last applied or undone move, from white player perspective.
+    ///
+    /// Renderers can redraw only these tiles.
+    #[cfg(test)]
+    pub fn dirty_squares(&self) -> &[Index] {
+        &self.dirty_squares
+    }
+
+    /// Source, captured and target tiles of `game_move`, from white player perspective.
+    fn changed_squares(&self, game_move: &AvailableMove, captured: &[Index]) -> Vec<Index> {
+        let mut squares = vec![self.board.to_white_index(game_move.source())];
+        squares.extend(
+            captured
+                .iter()
+                .map(|index| self.board.to_white_index(*index)),
+        );
+        squares.push(self.board.to_white_index(game_move.target()));
+
+        squares
+    }
+
+    /// Full-move number, starts at 1 and grows after every black move.
+    pub fn turn_number(&self) -> usize {
+        self.turn_number
+    }
+
+    /// Takes back the last move, returns `false` if there is nothing to undo.
+    pub fn undo_move(&mut self) -> bool {
+        let (game_move, record) = match (self.history.pop(), self.undo_stack.pop()) {
+            (Some(game_move), Some(record)) => (game_move, record),
+            _ => return false,
+        };
+
+        // Put pawn back in source, it could have been promoted on the way
+        self.board
+            .set_tile(game_move.target(), Tile::Empty)
+            .expect(INTERNAL_ERROR_MESSAGE);
+        self.board
+            .set_tile(game_move.source(), record.pawn)
+            .expect(INTERNAL_ERROR_MESSAGE);
+        let captured: Vec<Index> = record.captured.iter().map(|(index, _)| *index).collect();
+        self.dirty_squares = self.changed_squares(&game_move, &captured);
+
+        // Restore captured pawns
+        for (index, tile) in record.captured {
+            self.board
+                .set_tile(index, tile)
+                .expect(INTERNAL_ERROR_MESSAGE);
+        }
+
+        self.state = record.state;
+        if self.state == GameState::Turn(Player::Black) {
+            self.turn_number -= 1;
+        }
+        self.attack_map.take();
+        if self.branching_factors.len() > self.history.len() {
+            self.branching_factors.pop();
+        }
+        self.redo_stack.push(game_move);
+
+        true
+    }
+
+    /// Replays the last undone move, returns `false` if there is nothing to redo.
+    pub fn redo_move(&mut self) -> bool {
+        let game_move = match self.redo_stack.pop() {
+            Some(game_move) => game_move,
+            None => return false,
+        };
+
+        // applying a move forgets undone moves, keep the remaining ones
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        self.branching_factors.push(self.branching_factor());
+        self.apply_move(&game_move);
+        self.redo_stack = redo_stack;
+
+        true
+    }
+
+    /// Undoes moves until `target_ply` plies remain.
+    pub fn undo_to(&mut self, target_ply: usize) -> Result<(), CheckersError> {
+        if target_ply > self.half_move_number() {
+            return Err(CheckersError::CannotReachPly(target_ply));
+        }
+
+        while self.half_move_number() > target_ply {
+            self.undo_move();
+        }
+
+        Ok(())
+    }
+
+    /// Undoes or redoes moves until `target_ply` plies were played.
+    pub fn goto_ply(&mut self, target_ply: usize) -> Result<(), CheckersError> {
+        if target_ply > self.half_move_number() + self.redo_stack.len() {
+            return Err(CheckersError::CannotReachPly(target_ply));
+        }
+
+        self.undo_to(target_ply.min(self.half_move_number()))?;
+        while self.half_move_number() < target_ply {
+            self.redo_move();
+        }
+
+        Ok(())
+    }
+
+    fn change_turn(&mut self) {
+        match self.state {
+            GameState::Won(_) | GameState::Draw(_) => panic!("The game has already ended!"),
+            GameState::Turn(player) => self.state = GameState::Turn(get_enemy(player)),
+        }
+    }
+
+    /// Ends the game when player to move has no legal moves.
+    ///
+    /// Player without any pawns always loses. Player with all pawns blocked loses
+    /// in standard checkers, or draws when `no_moves_is_draw` rule is set.
+    fn check_game_over(&mut self) {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return,
+        };
+
+        if self.has_any_move(player) {
+            return;
+        }
+
+        self.state = if self.rules.no_moves_is_draw && self.board.has_pawns(player) {
+            GameState::Draw(DrawReason::NoMoves)
+        } else {
+            GameState::Won(get_enemy(player))
+        };
+    }
+
+    fn has_any_move(&self, player: Player) -> bool {
+        !self.legal_moves_for(player).is_empty()
+    }
+
+    pub fn has_any_capture_for(&self, player: Player) -> bool {
+        !self.legal_captures_for(player).is_empty()
+    }
+
+    /// Player to move has no captures, so static evaluation of the position is reliable.
+    ///
+    /// Ended game is quiet.
+    pub fn is_quiet(&self) -> bool {
+        match self.state {
+            GameState::Turn(player) => !self.has_any_capture_for(player),
+            _ => true,
+        }
+    }
+
+    /// Each player has a single king and nothing else, and the player to move
+    /// can neither capture nor is forced to give its king away.
+    ///
+    /// Shallow check, lets the AI stop chasing unwinnable endgames. Doesn't end the game.
+    pub fn is_theoretical_draw(&self) -> bool {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return false,
+        };
+
+        let lone_kings = self
+            .board
+            .tiles
+            .iter()
+            .all(|tile| *tile == Tile::Empty || tile.is_king())
+            && self
+                .board
+                .tiles
+                .iter()
+                .filter(|tile| tile.is_king())
+                .count()
+                == 2
+            && self.board.has_king(Player::White)
+            && self.board.has_king(Player::Black);
+        if !lone_kings || self.has_any_capture_for(player) {
+            return false;
+        }
+
+        // mutual zugzwang, king cornered by the enemy king loses whatever it plays
+        self.legal_moves_for(player).iter().any(|available_move| {
+            let mut game = self.clone();
+            game.apply_move(available_move);
+
+            !game.has_any_capture_for(get_enemy(player))
+        })
+    }
+
+    /// Captures of the player to move taking pawns worth more than the capturing pawn,
+    /// see `Tile::piece_value`.
+    ///
+    /// Without such captures returns the ones taking exactly the capturing pawn's worth.
+    #[cfg(test)]
+    pub fn get_material_winning_captures(&self) -> Vec<AvailableMove> {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return Vec::new(),
+        };
+
+        let (winning, even): (Vec<AvailableMove>, Vec<AvailableMove>) = self
+            .legal_captures_for(player)
+            .into_iter()
+            .filter(|capture| self.capture_material_gain(capture) >= 0)
+            .partition(|capture| self.capture_material_gain(capture) > 0);
+
+        if winning.is_empty() {
+            even
+        } else {
+            winning
+        }
+    }
+
+    /// Worth of pawns taken by the move minus worth of the moving pawn, 0 for non captures.
+    fn capture_material_gain(&self, available_move: &AvailableMove) -> i32 {
+        if !available_move.is_capture() {
+            return 0;
+        }
+
+        let captured: i32 = self
+            .board
+            .get_captured_indices(available_move)
+            .iter()
+            .filter_map(|index| self.board.get_tile(*index).ok())
+            .map(|tile| tile.piece_value())
+            .sum();
+        let attacker = self
+            .board
+            .get_tile(available_move.source())
+            .map_or(0, |tile| tile.piece_value());
+
+        captured - attacker
+    }
+
+    /// Plies since the last capture or man move, those moves can't be undone over the board.
+    pub fn half_move_clock(&self) -> usize {
+        self.undo_stack
+            .iter()
+            .rev()
+            .take_while(|record| record.pawn.is_king() && record.captured.is_empty())
+            .count()
+    }
+
+    /// How many times the current position occurred, counting the current one.
+    ///
+    /// Positions are compared with `same_position`.
+    pub fn repetition_count(&self) -> usize {
+        let mut game = self.clone();
+        let mut count = 1;
+
+        // positions before a capture or man move can't occur again
+        for _ in 0..self.half_move_clock() {
+            game.undo_move();
+            if game.same_position(self) {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Each player has a single king and nothing else, so neither can win
+    /// without the other one blundering.
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut pawns = self.board.tiles.iter().filter(|tile| **tile != Tile::Empty);
+
+        pawns.clone().count() == 2
+            && pawns.all(|tile| tile.is_king())
+            && self.board.has_king(Player::White)
+            && self.board.has_king(Player::Black)
+    }
+
+    /// First reason the player to move could claim a draw for, `None` if the game
+    /// has ended or there is none.
+    pub fn can_draw_be_claimed(&self) -> Option<DrawReason> {
+        if self.is_game_over() {
+            return None;
+        }
+
+        if self.repetition_count() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.half_move_clock() >= self.rules.draw_ply_limit {
+            Some(DrawReason::InactivityLimit)
+        } else if self.is_insufficient_material() {
+            Some(DrawReason::InsufficientMaterial)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the game with a draw for the `can_draw_be_claimed` reason.
+    pub fn claim_draw(&mut self) -> Result<(), CheckersError> {
+        if self.is_game_over() {
+            return Err(CheckersError::GameOver);
+        }
+
+        let reason = self
+            .can_draw_be_claimed()
+            .ok_or(CheckersError::NoDrawToClaim)?;
+        self.state = GameState::Draw(reason);
+
+        Ok(())
+    }
+
+    /// Source and number of captured pawns of the longest capture of the player to move.
+    ///
+    /// Ties are resolved by the `legal_captures_for` order.
+    #[cfg(test)]
+    pub fn longest_capture(&self) -> Option<(Index, usize)> {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return None,
+        };
+
+        self.legal_captures_for(player)
+            .iter()
+            .rev()
+            .max_by_key(|capture| capture.capture_count())
+            .map(|capture| (capture.source(), capture.capture_count()))
+    }
+
+    /// Enemy pawns the player to move can capture with some legal move, from white
+    /// player perspective.
+    pub fn capturable_enemy_pieces(&self) -> Vec<Index> {
+        let mut pieces: Vec<Index> = self
+            .legal_moves()
+            .iter()
+            .filter(|available_move| available_move.is_capture())
+            .flat_map(|capture| self.board.get_captured_indices(capture))
+            .map(|index| self.board.to_white_index(index))
+            .collect();
+
+        pieces.sort();
+        pieces.dedup();
+        pieces
+    }
+
+    /// Recommendation for a position from the `OPENINGS` table, looked up by FEN.
+    pub fn suggest_opening(&self) -> Option<String> {
+        let fen = self.to_fen();
+
+        OPENINGS
+            .iter()
+            .find(|(prefix, _, _)| fen.starts_with(prefix))
+            .map(|(_, suggested_move, name)| {
+                format!(
+                    "This is the {} opening. Recommended: {}",
+                    name, suggested_move
+                )
+            })
+    }
+
+    /// Checks if both games have the same board and the same player to move.
+    ///
+    /// History and rules are ignored, this is the equality used for repetitions.
+    pub fn same_position(&self, other: &Game) -> bool {
+        self.board == other.board && self.state == other.state
+    }
+
+    /// Canonical board of the position with the state seen from its side.
+    ///
+    /// When `Board::canonical` swaps colors the players in the state are swapped too, so
+    /// symmetric positions share the result, but the same board with the other player to
+    /// move doesn't.
+    #[cfg(test)]
+    pub fn canonical_position(&self) -> (Board, GameState) {
+        let (board, swapped) = self.board.canonical();
+        let state = match self.state {
+            GameState::Turn(player) if swapped => GameState::Turn(get_enemy(player)),
+            GameState::Won(player) if swapped => GameState::Won(get_enemy(player)),
+            state => state,
+        };
+
+        (board, state)
+    }
+
+    /// Checks if both games have the same board, whoever is to move.
+    ///
+    /// Unlike `same_position`, the position with white to move equals the one with
+    /// black to move, which is useful for analysis of pawn structures.
+    #[cfg(test)]
+    pub fn same_position_ignoring_turn(&self, other: &Game) -> bool {
+        self.board == other.board
+    }
+
+    /// Checks if `game_move` leaves the opponent with captures as the only legal moves.
+    #[cfg(test)]
+    pub fn is_forcing(&self, game_move: Move) -> Result<bool, CheckersError> {
+        let available_move = self.check_move(game_move)?;
+
+        let mut game = self.clone();
+        game.apply_move(&available_move);
+
+        let replies = game.legal_moves();
+        Ok(!replies.is_empty() && replies.iter().all(|reply| reply.is_capture()))
+    }
+
+    /// Legal moves for the player to move, empty when the game has ended.
+    pub fn legal_moves(&self) -> Vec<AvailableMove> {
+        match self.state {
+            GameState::Turn(player) => self.legal_moves_for(player),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Number of move sequences of exactly `depth` plies, ended games are dead ends.
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return 0,
+        };
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        // counting only needs the board and the player to move, so children skip
+        // the history and undo bookkeeping of `apply_move`
+        let mut child = self.clone();
+        child.state = GameState::Turn(get_enemy(player));
+
+        moves
+            .iter()
+            .map(|available_move| {
+                child.board = self
+                    .board
+                    .apply_moves_batch(&self.board.get_move_steps(available_move))
+                    .expect(INTERNAL_ERROR_MESSAGE);
+                child.attack_map.take();
+
+                child.perft(depth - 1)
+            })
+            .sum()
+    }
+
+    /// Every legal move paired with the game after it, `self` is left untouched.
+    #[cfg(test)]
+    pub fn successors(&self) -> Vec<(AvailableMove, Game)> {
+        self.legal_moves()
+            .into_iter()
+            .map(|available_move| {
+                let mut child = self.clone();
+                child.apply_move(&available_move);
+
+                (available_move, child)
+            })
+            .collect()
+    }
+
+    /// `perft` of every depth from 1 to `max_depth`, paired with the depth.
+    pub fn perft_breakdown(&self, max_depth: usize) -> Vec<(usize, u64)> {
+        (1..=max_depth)
+            .map(|depth| (depth, self.perft(depth)))
+            .collect()
+    }
+
+    /// White tempo minus black tempo, see `Board::compute_tempo`.
+    pub fn tempo_difference(&self) -> i32 {
+        self.board.compute_tempo(Player::White) as i32
+            - self.board.compute_tempo(Player::Black) as i32
+    }
+
+    /// Prints board with legal destinations of the pawn on `source` highlighted.
+    pub fn show_board_with_move_preview(&self, source: Index) {
+        let targets = self.destinations_for(source);
+
+        println!("{}", self.board.get_drawed_board_preview(source, &targets));
+    }
+
+    /// Legal moves of the pawn on `source` in algebraic notation, for the player to move.
+    ///
+    /// Fails only if the tile can't be parsed.
+    pub fn get_moves_from_source(&self, source: &str) -> Result<Vec<AvailableMove>, CheckersError> {
+        let source = self.board.parse_notation(&source.to_uppercase())?;
+
+        Ok(self
+            .legal_moves()
+            .into_iter()
+            .filter(|available_move| self.board.to_white_index(available_move.source()) == source)
+            .collect())
+    }
+
+    /// Targets of legal moves of the pawn on `source`, for the player to move.
+    pub fn destinations_for(&self, source: Index) -> Vec<Index> {
+        let source = self.board.to_white_index(source);
+
+        self.legal_moves()
+            .into_iter()
+            .filter(|available_move| self.board.to_white_index(available_move.source()) == source)
+            .map(|available_move| available_move.target())
+            .collect()
+    }
+
+    /// Legal moves of the player to move, keyed by source index of the mover perspective.
+    #[cfg(test)]
+    pub fn legal_moves_by_source(&self) -> HashMap<Index, Vec<AvailableMove>> {
+        let mut moves_by_source: HashMap<Index, Vec<AvailableMove>> = HashMap::new();
+
+        for available_move in self.legal_moves() {
+            moves_by_source
+                .entry(available_move.source())
+                .or_default()
+                .push(available_move);
+        }
+
+        moves_by_source
+    }
+
+    /// Legal moves without quiet moves that let the opponent capture a pawn it couldn't
+    /// capture before.
+    ///
+    /// Beginner assist only, those moves are still legal. Captures are always kept.
+    pub fn moves_avoiding_recapture(&self) -> Vec<AvailableMove> {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return Vec::new(),
+        };
+        let capturable_before = self.get_capturable_indices(get_enemy(player));
+
+        self.legal_moves()
+            .into_iter()
+            .filter(|available_move| {
+                if available_move.is_capture() {
+                    return true;
+                }
+
+                let mut game = self.clone();
+                game.apply_move(available_move);
+
+                game.get_capturable_indices(get_enemy(player))
+                    .iter()
+                    .all(|index| capturable_before.contains(index))
+            })
+            .collect()
+    }
+
+    /// Indices of pawns that `player` could capture, including every pawn of multi
+    /// captures, from white player perspective.
+    fn get_capturable_indices(&self, player: Player) -> Vec<Index> {
+        let mut indices: Vec<Index> = self
+            .legal_captures_for(player)
+            .iter()
+            .flat_map(|capture| self.board.get_captured_indices(capture))
+            .map(|index| self.board.to_white_index(index))
+            .collect();
+        indices.sort();
+        indices.dedup();
+
+        indices
+    }
+
+    /// Checks if `by` could recapture on `index` after a pawn standing there is captured.
+    ///
+    /// Pawn capturing along a diagonal lands right behind `index`, so `by` recaptures
+    /// with a pawn two tiles behind `index`, jumping back onto it. Unlike
+    /// `AttackMap::is_attacked_by`, this doesn't tell whether the capture is possible.
+    #[cfg(test)]
+    pub fn is_defended(&self, index: Index, by: Player) -> bool {
+        Direction::all().iter().any(|direction| {
+            let (dx, dy) = direction.offset();
+
+            let landing_index = match index.translate(dx, dy) {
+                Some(landing_index) if self.board.validate_index(landing_index) => landing_index,
+                _ => return false,
+            };
+            let defender_index = match index.translate(2 * dx, 2 * dy) {
+                Some(defender_index) if self.board.validate_index(defender_index) => defender_index,
+                _ => return false,
+            };
+
+            self.board.get_tile(landing_index) == Ok(Tile::Empty)
+                && self.board.get_tile_owner_at(defender_index) == Ok(Some(by))
+        })
+    }
+
+    /// Finds a move that immediately wins the game for the player to move.
+    pub fn find_winning_move(&self) -> Option<AvailableMove> {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return None,
+        };
+
+        self.legal_moves().into_iter().find(|available_move| {
+            let mut game = self.clone();
+            game.apply_move(available_move);
+
+            game.state == GameState::Won(player)
+        })
+    }
+
+    /// Search-based zugzwang detection, compares `depth` search score of the player
+    /// to move with the score after passing the turn.
+    ///
+    /// Position is zugzwang when passing would gain at least `ZUGZWANG_MARGIN`.
+    /// Passing is searched one ply shallower, so horizon effects can report
+    /// false positives: a tactic found only by the full search, or evaluation swings
+    /// of the shallower search. Shallow checks report them most often, the false
+    /// positive rate drops as `depth` grows. Over 8859 positions of 150 random 8x8 games
+    /// depth 2 reported 24 positions (0.3%), only 5 of them confirmed at depth 4, so
+    /// about 4 of 5 depth 2 reports are false positives.
+    ///
+    /// `Minimax` runs it at `ZUGZWANG_CHECK_DEPTH` before every search, a false positive
+    /// only turns null move pruning off.
+    pub fn zugzwang_check(&self, depth: usize) -> bool {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return false,
+        };
+        let minimax = Minimax::new(depth);
+
+        let score = minimax.search(self, player, depth, 0, -WIN_SCORE, WIN_SCORE);
+        let threshold = score.saturating_add(ZUGZWANG_MARGIN);
+
+        let mut game = self.clone();
+        game.apply_null_move();
+
+        // null window, only asks whether enemy score is at most -threshold
+        let null_score = -minimax.search(
+            &game,
+            get_enemy(player),
+            depth.saturating_sub(1),
+            1,
+            -threshold,
+            -threshold + 1,
+        );
+
+        null_score >= threshold
+    }
+
+    /// Passes the turn without moving any pawn, used by search heuristics.
+    ///
+    /// Pass isn't recorded in the game history.
+    pub fn apply_null_move(&mut self) {
+        self.change_turn();
+        self.check_game_over();
+    }
+
+    /// Moves of the player to move that leave at least two enemy pawns under attack,
+    /// where fewer were attacked before the move.
+    pub fn get_fork_moves(&self) -> Vec<AvailableMove> {
+        let player = match self.state {
+            GameState::Turn(player) => player,
+            _ => return Vec::new(),
+        };
+        let attacked_before = self.attacked_pieces_count(get_enemy(player));
+
+        self.legal_moves()
+            .into_iter()
+            .filter(|available_move| {
+                let mut game = self.clone();
+                game.apply_move(available_move);
+
+                game.is_fork_of(player, attacked_before)
+            })
+            .collect()
+    }
+
+    /// Number of `player` pawns under attack, from the cached attack map.
+    fn attacked_pieces_count(&self, player: Player) -> usize {
+        self.board
+            .get_pieces_under_attack_with(player, self.get_attack_map())
+            .len()
+    }
+
+    /// Checks if the position after a `player` move attacks at least two enemy pawns,
+    /// more than the `attacked_before` ones attacked before the move.
+    fn is_fork_of(&self, player: Player, attacked_before: usize) -> bool {
+        let attacked_after = self.attacked_pieces_count(get_enemy(player));
+        attacked_after >= 2 && attacked_after > attacked_before
+    }
+
+    /// Finds `player` kings with two enemy pawns one after another on the same diagonal,
+    /// as `(king_index, front_piece, back_piece)` triples from white player perspective.
+    ///
+    /// Tiles between the king and the front pawn and between both pawns have to be empty.
+    /// Only meaningful for flying kings, which can reach the front pawn from a distance.
+    pub fn get_skewer_threats(&self, player: Player) -> Vec<(Index, Index, Index)> {
+        let enemy = get_enemy(player);
+        let mut skewers = Vec::new();
+
+        for king_index in self.board.get_pieces(player) {
+            if !self
+                .board
+                .get_tile(king_index)
+                .is_ok_and(|tile| tile.is_king())
+            {
+                continue;
+            }
+
+            for direction in Direction::all().iter() {
+                let (dx, dy) = direction.offset();
+
+                // first two pawns met when sliding along the diagonal
+                let mut pieces = Vec::new();
+                let mut index = king_index;
+                while let Some(next_index) = index.translate(dx, dy) {
+                    if !self.board.validate_index(next_index) || pieces.len() == 2 {
+                        break;
+                    }
+                    index = next_index;
+
+                    if self.board.get_tile(index) != Ok(Tile::Empty) {
+                        pieces.push(index);
+                    }
+                }
+
+                if pieces.len() == 2
+                    && pieces
+                        .iter()
+                        .all(|index| self.board.get_tile_owner_at(*index) == Ok(Some(enemy)))
+                {
+                    skewers.push((king_index, pieces[0], pieces[1]));
+                }
+            }
+        }
+
+        skewers
+    }
+
+    /// Legal moves of every `player` pawn, generated as if `player` was to move.
+    ///
+    /// Ignores turn order and game state, so it can be used to ask what the waiting
+    /// player could do in the current position.
+    ///
+    /// Moves are sorted by source and then target index, see `Index` ordering.
+    pub fn legal_moves_for(&self, player: Player) -> Vec<AvailableMove> {
+        self.collect_moves_for(player, |source| self.get_allowed_moves_for(source))
+    }
+
+    /// Legal captures of every `player` pawn, same as captures in `legal_moves_for`.
+    pub fn legal_captures_for(&self, player: Player) -> Vec<AvailableMove> {
+        self.collect_moves_for(player, |source| self.get_captures_for(source))
+    }
+
+    /// Moves generated by `generate` for every `player` pawn, sorted and filtered
+    /// by capture rules.
+    fn collect_moves_for<F>(&self, player: Player, generate: F) -> Vec<AvailableMove>
+    where
+        F: Fn(Index) -> Result<Vec<AvailableMove>, CheckersError>,
+    {
+        let mut available_moves = Vec::new();
+
+        for y in 0..self.board.height() {
+            for x in 0..self.board.width() {
+                let index = Index::new(x, y, Player::White);
+                if self.board.get_tile_owner_at(index) != Ok(Some(player)) {
+                    continue;
+                }
+
+                // moves have to be generated from the pawn owner perspective
+                let source = match player {
+                    Player::White => index,
+                    Player::Black => self.board.reverse_index(&index),
+                };
+
+                if let Ok(moves) = generate(source) {
+                    available_moves.extend(moves);
+                }
+            }
+        }
+
+        // stable order, independent of generation order
+        available_moves
+            .sort_by_key(|available_move| (available_move.source(), available_move.target()));
+
+        let mut available_moves = self.apply_capture_rules(available_moves);
+
+        // stable sort keeps source order among equal counts
+        if self.rules.capture_ordering == CaptureOrdering::ByCaptureCount {
+            available_moves
+                .sort_by_key(|available_move| std::cmp::Reverse(available_move.capture_count()));
+        }
+
+        self.apply_promotion_rule(available_moves)
+    }
+
+    /// Filters moves of a single player by `forced_promotion` rule.
+    fn apply_promotion_rule(&self, available_moves: Vec<AvailableMove>) -> Vec<AvailableMove> {
+        if !self.rules.forced_promotion
+            || !available_moves
+                .iter()
+                .any(|available_move| self.is_promotion(available_move))
+        {
+            return available_moves;
+        }
+
+        available_moves
+            .into_iter()
+            .filter(|available_move| self.is_promotion(available_move))
+            .collect()
+    }
+
+    /// Checks if `available_move` makes a man reach the last row.
+    fn is_promotion(&self, available_move: &AvailableMove) -> bool {
+        self.board
+            .get_tile(available_move.source())
+            .is_ok_and(|tile| tile != Tile::Empty && !tile.is_king())
+            && available_move.path().iter().any(|index| index.y == 0)
+    }
+
+    /// Checks if the player to move has to promote a man, see `RulesConfig::forced_promotion`.
+    #[cfg(test)]
+    pub fn promotion_forced(&self) -> bool {
+        self.rules.forced_promotion
+            && self
+                .legal_moves()
+                .iter()
+                .any(|available_move| self.is_promotion(available_move))
+    }
+
+    /// Filters moves of a single player by `max_capture` and `max_kings_capture` rules.
+    fn apply_capture_rules(&self, available_moves: Vec<AvailableMove>) -> Vec<AvailableMove> {
+        if !self.rules.max_capture {
+            return available_moves;
+        }
+
+        let captured_counts = |available_move: &AvailableMove| {
+            if !self.rules.max_kings_capture {
+                return (available_move.capture_count(), 0);
+            }
+
+            let kings = self
+                .board
+                .get_captured_indices(available_move)
+                .iter()
+                .filter(|index| {
+                    self.board
+                        .get_tile(**index)
+                        .is_ok_and(|tile| tile.is_king())
+                })
+                .count();
+
+            (available_move.capture_count(), kings)
+        };
+
+        // quiet moves count as zero captures, so any capture excludes them
+        let best = match available_moves.iter().map(captured_counts).max() {
+            Some(best) => best,
+            None => return available_moves,
+        };
+
+        available_moves
+            .into_iter()
+            .filter(|available_move| captured_counts(available_move) == best)
+            .collect()
+    }
+
+    /// Number of `player` moves needed to force a win, with best play from both sides.
+    ///
+    /// Returns `None` if there is no forced win within `max_depth` moves of `player`,
+    /// `Some(1)` if `player` wins with its next move.
+    pub fn get_forced_win_length(&self, player: Player, max_depth: usize) -> Option<usize> {
+        if max_depth == 0 {
+            return None;
+        }
+
+        // player to move uses one ply less to make the same number of moves
+        let max_plies = match self.state {
+            GameState::Turn(to_move) if to_move == player => 2 * max_depth - 1,
+            _ => 2 * max_depth,
+        };
+
+        Minimax::plies_to_win(self, player, max_plies).map(|plies| plies.div_ceil(2))
+    }
+
+    /// Finds empty dark squares where putting a `player` man lets `player`
+    /// force a win within `depth` moves.
+    ///
+    /// Runs full `Minimax` search for every empty square, so it is meant for offline analysis.
+    /// `depth` is capped at `CRITICAL_SQUARES_MAX_DEPTH`.
+    #[cfg(test)]
+    pub fn get_critical_squares(&self, player: Player, depth: usize) -> Vec<Index> {
+        let minimax = Minimax::new(depth.min(CRITICAL_SQUARES_MAX_DEPTH));
+        let (man, king, promotion_row) = match player {
+            Player::White => (Tile::White, Tile::WhiteKing, 0),
+            Player::Black => (Tile::Black, Tile::BlackKing, self.board.height() - 1),
+        };
+
+        let mut critical_squares = Vec::new();
+        for y in 0..self.board.height() {
+            for x in 0..self.board.width() {
+                let index = Index::new(x, y, Player::White);
+                if index.square_color().is_light() || self.board.get_tile(index) != Ok(Tile::Empty)
+                {
+                    continue;
+                }
+
+                // man can't stand on its promotion row, it is a king there
+                let pawn = if y == promotion_row { king } else { man };
+
+                let mut game = self.clone();
+                game.board
+                    .set_tile(index, pawn)
+                    .expect(INTERNAL_ERROR_MESSAGE);
+                game.attack_map.take();
+                game.state = GameState::Turn(player);
+
+                if let Some((_, score)) = minimax.best_move(&game) {
+                    if Minimax::is_win_score(score) {
+                        critical_squares.push(index);
+                    }
+                }
+            }
+        }
+
+        critical_squares
+    }
+
+    /// Rates `game_move` of the player to move by comparing it with the best move
+    /// found by `Minimax` search at `depth`.
+    pub fn evaluate_move_quality(&self, game_move: &AvailableMove, depth: usize) -> MoveQuality {
+        let minimax = Minimax::new(depth);
+        let best_score = match minimax.best_move(self) {
+            Some((_, score)) => score,
+            None => return MoveQuality::BestMove,
+        };
+
+        let loss = best_score
+            .saturating_sub(minimax.score_move(self, game_move))
+            .max(0);
+        MoveQuality::from_centipawn_loss(loss)
+    }
+
+    /// Parses move notation to an Index:
+    /// Ex. `A6 B5` or `B1 C2`.
+    ///
+    /// Multi captures may list every landing tile, ex. `A8 C6 E4 G2`, to pick one of
+    /// captures ending on the same tile.
+    ///
+    /// The move is indexed from the perspective of the player to move.
+    fn parse_move_description(&self, description: &str) -> Result<Move, CheckersError> {
+        lazy_static! {
+            static ref ALGEBRAIC_NOTATION_REGEX: Regex =
+                Regex::new("([A-Z])([0-9]+)((?: [A-Z][0-9]+)*) ([A-Z])([0-9]+)").unwrap();
+        }
+
+        if !ALGEBRAIC_NOTATION_REGEX.is_match(description) {
+            return Err(CheckersError::InvalidNotation(
+                "Move does not match required notation",
+            ));
+        }
+
+        // we can safely unwrap all of below, because regex is matching as per check above
+        let captures = ALGEBRAIC_NOTATION_REGEX
+            .captures_iter(description)
+            .nth(0)
+            .unwrap();
+
+        let source_letter: char = captures[1].chars().nth(0).unwrap();
+        let source_number: i32 = captures[2].parse().unwrap();
+        let target_letter: char = captures[4].chars().nth(0).unwrap();
+        let target_number: i32 = captures[5].parse().unwrap();
+        let path = captures[3]
+            .split_whitespace()
+            .map(|square| {
+                self.board.parse_notation(square).map_err(|_| {
+                    CheckersError::InvalidNotation("Capture path index is outside the board")
+                })
+            })
+            .collect::<Result<Vec<Index>, CheckersError>>()?;
+
+        // horizontal indeces are created from A-Z letters
+        // we can use their char code value and subtract A value
+        // vertical indeces are created from 1-based numbers
+        // we only have to subtract 1
+        // TODO: try to refactor this code
+        let source_horizontal_index = match usize::try_from((source_letter as i32) - 65) {
+            Ok(number) => {
+                if number < self.board.height() {
+                    number
+                } else {
+                    return Err(CheckersError::InvalidNotation(
+                        "Horizontal source index is outside the board",
+                    ));
+                }
+            }
+            Err(_) => {
+                return Err(CheckersError::InvalidNotation(
+                    "Horizontal source index is outside the board",
+                ))
+            }
+        };
+        let source_vertical_index = match usize::try_from(source_number - 1) {
+            Ok(number) => {
+                if number < self.board.height() {
+                    number
+                } else {
+                    return Err(CheckersError::InvalidNotation(
+                        "Vertical source index is outside the board",
+                    ));
+                }
+            }
+            Err(_) => {
+                return Err(CheckersError::InvalidNotation(
+                    "Vertical source index is outside the board",
+                ))
+            }
+        };
+        let target_horizontal_index = match usize::try_from((target_letter as i32) - 65) {
+            Ok(number) => {
+                if number < self.board.height() {
+                    number
+                } else {
+                    return Err(CheckersError::InvalidNotation(
+                        "Horizontal target index is outside the board",
+                    ));
+                }
+            }
+            Err(_) => {
+                return Err(CheckersError::InvalidNotation(
+                    "Horizontal target index is outside the board",
+                ))
+            }
+        };
+        let target_vertical_index = match usize::try_from(target_number - 1) {
+            Ok(number) => {
+                if number < self.board.height() {
+                    number
+                } else {
+                    return Err(CheckersError::InvalidNotation(
+                        "Vertical target index is outside the board",
+                    ));
+                }
+            }
+            Err(_) => {
+                return Err(CheckersError::InvalidNotation(
+                    "Vertical target index is outside the board",
+                ))
+            }
+        };
+
+        let mut game_move = Move::new(
+            Index::new(
+                source_horizontal_index,
+                source_vertical_index,
+                Player::White,
+            ),
+            Index::new(
+                target_horizontal_index,
+                target_vertical_index,
+                Player::White,
+            ),
+        );
+        game_move.path = path;
+
+        match self.state {
+            GameState::Turn(Player::White) => Ok(game_move),
+            GameState::Turn(Player::Black) => Ok(game_move.reversed(&self.board)),
+            _ => panic!("The game has ended already"),
+        }
+    }
+
+    /// Whether `check_move` accepts the move.
+    ///
+    /// Use `check_move` to learn why a move is illegal, or to get the `AvailableMove`
+    /// needed to apply it.
+    #[cfg(test)]
+    pub fn is_legal(&self, game_move: Move) -> bool {
+        self.check_move(game_move).is_ok()
+    }
+
+    /// Checks if the pawn on `source` can move to `target`, both in algebraic notation.
+    ///
+    /// Only the pawn moves are checked, not the turn or capture rules.
+    /// Fails only if a tile can't be parsed.
+    #[cfg(test)]
+    pub fn is_legal_move(&self, source: &str, target: &str) -> Result<bool, CheckersError> {
+        let source = self.board.parse_notation(&source.to_uppercase())?;
+        let target = self.board.parse_notation(&target.to_uppercase())?;
+
+        // moves have to be generated from the pawn owner perspective
+        let source = match self.board.get_tile_owner_at(source) {
+            Ok(Some(Player::Black)) => self.board.reverse_index(&source),
+            _ => source,
+        };
+
+        Ok(self
+            .get_allowed_moves_for(source)
+            .unwrap_or_default()
+            .iter()
+            .any(|available_move| self.board.to_white_index(available_move.target()) == target))
+    }
+
+    pub fn check_move(&self, game_move: Move) -> Result<AvailableMove, CheckersError> {
+        if self.is_game_over() {
+            return Err(CheckersError::GameOver);
+        };
+
+        if trace_enabled() {
+            eprintln!("[trace] check_move({:?})", game_move);
+        }
+
+        // Check if there is a pawn to move, before generating its moves
+        if self.board.get_tile(game_move.source) == Ok(Tile::Empty) {
+            return Err(CheckersError::EmptySource);
+        }
+
+        // check if move is valid
+        let available_moves = self.get_allowed_moves_for(game_move.source)?;
+
+        if trace_enabled() {
+            eprintln!("[trace] available moves: {:?}", available_moves);
+        }
+
+        let available_move = Game::find_move_in_available(available_moves, &game_move)
+            .ok_or(CheckersError::IllegalMove)?;
+
+        // capture and promotion rules depend on moves of all player pawns
+        if (self.rules.max_capture || self.rules.forced_promotion)
+            && !self
+                .legal_moves_for(game_move.source.orientation)
+                .contains(&available_move)
+        {
+            return Err(CheckersError::IllegalMove);
+        }
+
+        Ok(available_move)
+    }
+
+    /// Finds the available move ending on `game_move` target.
+    ///
+    /// Multi-captures are matched by their last landing tile, single moves are preferred.
+    /// A move with a path only matches the multi capture landing on the same tiles.
+    pub fn find_move_in_available(
+        available_moves: Vec<AvailableMove>,
+        game_move: &Move,
+    ) -> Option<AvailableMove> {
+        let (multi_captures, single_moves): (Vec<AvailableMove>, Vec<AvailableMove>) =
+            available_moves.into_iter().partition(|x| {
+                matches!(
+                    x.move_type(),
+                    MoveType::MultiCapture(_) | MoveType::KingMultiCapture(_)
+                )
+            });
+
+        single_moves.into_iter().chain(multi_captures).find(|x| {
+            let landing_tiles = x.path();
+            x.target() == game_move.target
+                && (game_move.path.is_empty()
+                    || landing_tiles[..landing_tiles.len() - 1] == game_move.path[..])
+        })
+    }
+
+    pub fn get_allowed_moves_for(
+        &self,
+        source: Index,
+    ) -> Result<Vec<AvailableMove>, CheckersError> {
+        let pawn = self
+            .board
+            .get_tile(source)
+            .map_err(|_| CheckersError::OutsideBoard)?;
+
+        // Check if source tile is empty
+        if pawn == Tile::Empty {
+            return Err(CheckersError::EmptySource);
+        }
+
+        let mut available_moves = Vec::new();
+
+        match pawn {
+            // Check for man moves
+            Tile::White | Tile::Black => {
+                // Check diagonal moves
+                if let Some(left_diagonal) = source.translate(-1, -1) {
+                    if self.board.get_tile(left_diagonal) == Ok(Tile::Empty) {
+                        available_moves
+                            .push(AvailableMove::new(source, MoveType::Move(left_diagonal)));
+                    }
+                }
+
+                if let Some(right_diagonal) = source.translate(1, -1) {
+                    if self.board.get_tile(right_diagonal) == Ok(Tile::Empty) {
+                        available_moves
+                            .push(AvailableMove::new(source, MoveType::Move(right_diagonal)));
+                    }
+                }
+            }
+            // Check for king moves
+            Tile::WhiteKing | Tile::BlackKing => {
+                // Kings step in every direction, flying kings slide until blocked
+                for direction in Direction::all().iter() {
+                    let (dx, dy) = direction.offset();
+                    let mut target = source.translate(dx, dy);
+                    while let Some(index) = target {
+                        if self.board.get_tile(index) != Ok(Tile::Empty) {
+                            break;
+                        }
+
+                        available_moves.push(AvailableMove::new(source, MoveType::KingMove(index)));
+
+                        if !self.rules.flying_kings {
+                            break;
+                        }
+                        target = index.translate(dx, dy);
+                    }
+                }
+            }
+            Tile::Empty => panic!("{}", INTERNAL_ERROR_MESSAGE),
+        }
+
+        available_moves.extend(self.get_captures_for(source)?);
+
+        Ok(available_moves)
+    }
+
+    /// Captures of the pawn on `source`, generated without its quiet moves.
+    ///
+    /// Every single capture is included, together with a multi-capture for every
+    /// capture chain longer than one jump.
+    pub fn get_captures_for(&self, source: Index) -> Result<Vec<AvailableMove>, CheckersError> {
+        let mut captures = self.get_single_captures_for(source)?;
+
+        let is_king = self.board.get_tile(source).is_ok_and(|pawn| pawn.is_king());
+        for path in self.get_capture_chains(source)? {
+            if path.len() < 2 {
+                continue;
+            }
+
+            let move_type = if is_king {
+                MoveType::KingMultiCapture(path)
+            } else {
+                MoveType::MultiCapture(path)
+            };
+            captures.push(AvailableMove::new(source, move_type));
+        }
+
+        Ok(captures)
+    }
+
+    /// Landing tiles of every capture chain starting on `source`, chains end when
+    /// the pawn can't capture again.
+    ///
+    /// Captured pawns are removed right after being jumped.
+    pub fn get_capture_chains(&self, source: Index) -> Result<Vec<Vec<Index>>, CheckersError> {
+        let mut chains = Vec::new();
+
+        for capture in self.get_single_captures_for(source)? {
+            let target = capture.target();
+
+            let pawn = self.board.get_tile(source).expect(INTERNAL_ERROR_MESSAGE);
+            let promoted = !pawn.is_king() && target.y == 0;
+            if promoted && !self.rules.king_continues_capture {
+                chains.push(vec![target]);
+                continue;
+            }
+
+            // Jump, promoted man continues the chain with king moves
+            let mut game = self.clone();
+            let pawn = match pawn {
+                Tile::White if promoted => Tile::WhiteKing,
+                Tile::Black if promoted => Tile::BlackKing,
+                pawn => pawn,
+            };
+            for index in self.board.get_captured_indices(&capture) {
+                game.board
+                    .set_tile(index, Tile::Empty)
+                    .expect(INTERNAL_ERROR_MESSAGE);
+            }
+            game.board
+                .set_tile(source, Tile::Empty)
+                .expect(INTERNAL_ERROR_MESSAGE);
+            game.board
+                .set_tile(target, pawn)
+                .expect(INTERNAL_ERROR_MESSAGE);
+
+            let continuations = game.get_capture_chains(target)?;
+            if continuations.is_empty() {
+                chains.push(vec![target]);
+            }
+            for continuation in continuations {
+                let mut chain = vec![target];
+                chain.extend(continuation);
+                chains.push(chain);
+            }
+        }
+
+        Ok(chains)
+    }
+
+    /// ASCII tree of capture chains starting on `source`, see `get_capture_chains`.
+    pub fn visualize_capture_chains(&self, source: Index) -> Result<String, CheckersError> {
+        let chains = self.get_capture_chains(source)?;
+        let chains: Vec<&[Index]> = chains.iter().map(|chain| chain.as_slice()).collect();
+
+        let mut tree = format!("{}\n", self.board.get_notation(source));
+        self.write_capture_tree(&mut tree, &chains, "");
+
+        Ok(tree)
+    }
+
+    /// Writes one tree level, chains sharing the first landing tile become one branch.
+    fn write_capture_tree(&self, tree: &mut String, chains: &[&[Index]], prefix: &str) {
+        // first landing tiles, in generation order
+        let mut branches: Vec<Index> = Vec::new();
+        for chain in chains {
+            if let Some(first) = chain.first() {
+                if !branches.contains(first) {
+                    branches.push(*first);
+                }
+            }
+        }
+
+        for (i, branch) in branches.iter().enumerate() {
+            let is_last = i + 1 == branches.len();
+            let (connector, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            tree.push_str(&format!(
+                "{}{}x {}\n",
+                prefix,
+                connector,
+                self.board.get_notation(*branch)
+            ));
+
+            let continuations: Vec<&[Index]> = chains
+                .iter()
+                .filter(|chain| chain.first() == Some(branch))
+                .map(|chain| &chain[1..])
+                .collect();
+            self.write_capture_tree(tree, &continuations, &format!("{}{}", prefix, indent));
+        }
+    }
+
+    /// Single jump captures of the pawn on `source`.
+    fn get_single_captures_for(&self, source: Index) -> Result<Vec<AvailableMove>, CheckersError> {
+        let pawn = self
+            .board
+            .get_tile(source)
+            .map_err(|_| CheckersError::OutsideBoard)?;
+
+        if pawn == Tile::Empty {
+            return Err(CheckersError::EmptySource);
+        }
+
+        // Men and kings capture in every direction, unless men capture only forward
+        let mut captures = Vec::new();
+        for direction in Direction::all().iter() {
+            if pawn.is_king() && self.rules.flying_kings {
+                captures.extend(self.check_flying_capture_moves(source, *direction));
+                continue;
+            }
+
+            let backward = matches!(direction, Direction::SE | Direction::SW);
+            if !pawn.is_king() && self.rules.men_capture_forward_only && backward {
+                continue;
+            }
+
+            if let Ok(capture) = self.check_capture_move(source, *direction) {
+                captures.push(capture);
+            }
+        }
+
+        Ok(captures)
+    }
+
+    /// Captures of a flying king on `source`, jumping the first pawn on the diagonal
+    /// and landing on any empty tile right behind it.
+    fn check_flying_capture_moves(
+        &self,
+        source: Index,
+        direction: Direction,
+    ) -> Vec<AvailableMove> {
+        let player = match self.board.get_tile_owner_at(source) {
+            Ok(Some(player)) => player,
+            _ => return Vec::new(),
+        };
+        let (dx, dy) = direction.offset();
+
+        // Skip empty tiles until the first pawn
+        let mut captured_index = source.translate(dx, dy);
+        while let Some(index) = captured_index {
+            if self.board.get_tile(index) != Ok(Tile::Empty) {
+                break;
+            }
+            captured_index = index.translate(dx, dy);
+        }
+
+        let captured_index = match captured_index {
+            Some(index) if self.board.get_tile_owner_at(index) == Ok(Some(get_enemy(player))) => {
+                index
+            }
+            _ => return Vec::new(),
+        };
+
+        // Every empty tile behind the captured pawn is a possible landing tile
+        let mut captures = Vec::new();
+        let mut target = captured_index.translate(dx, dy);
+        while let Some(target_index) = target {
+            if self.board.get_tile(target_index) != Ok(Tile::Empty) {
+                break;
+            }
+
+            captures.push(AvailableMove::new(
+                source,
+                MoveType::KingCapture {
+                    target_index,
+                    captured_index,
+                },
+            ));
+            target = target_index.translate(dx, dy);
+        }
+
+        captures
+    }
+
+    fn check_capture_move(
+        &self,
+        source: Index,
+        direction: Direction,
+    ) -> Result<AvailableMove, &str> {
+        // Check if source is a pawn on the board
+        let source_tile = self.board.get_tile(source)?;
+
+        // Check if source tile is not empty
+        let player = match get_tile_owner(source_tile) {
+            Some(player) => player,
+            None => return Err("Source tile is empty"),
+        };
+        let enemy_player = get_enemy(player);
+
+        // Get target tile index
+        let target = match direction {
+            Direction::NW => source.translate(-2, -2),
+            Direction::NE => source.translate(2, -2),
+            Direction::SE => source.translate(2, 2),
+            Direction::SW => source.translate(-2, 2),
+        };
+
+        // Check if we could translate index
+        let target_index = match target {
+            None => return Err("Target tile is outside of the board"),
+            Some(index) => index,
+        };
+
+        // Check if target is on the board
+        let target_tile = self.board.get_tile(target_index)?;
+
+        // Check if target tile is empty
+        if target_tile != Tile::Empty {
+            return Err("Target tile is not empty");
+        }
+
+        // Get captured tile index
+        let captured_index = match direction {
+            Direction::NW => source.translate(-1, -1),
+            Direction::NE => source.translate(1, -1),
+            Direction::SE => source.translate(1, 1),
+            Direction::SW => source.translate(-1, 1),
+        }
+        // Captured tile is between target and source, so it has to be on the board
+        .unwrap();
+
+        // Captured tile is between target and source, so it has to be on the board
+        let captured_tile = self.board.get_tile(captured_index).unwrap();
+
+        // Check if captured tile belongs to enemy
+        if get_tile_owner(captured_tile) != Some(enemy_player) {
+            return Err("Tile to be captured does not belong to enemy");
+        }
+
+        let move_type = if source_tile.is_king() {
+            MoveType::KingCapture {
+                target_index,
+                captured_index,
+            }
+        } else {
+            MoveType::Capture {
+                target_index,
+                captured_index,
+            }
+        };
+
+        Ok(AvailableMove::new(source, move_type))
+    }
+
+    /// Prints every evaluation component of the current position from `player` perspective.
+    pub fn print_evaluation_breakdown(&self, player: Player) {
+        let components = Minimax::new(0).evaluate_components(self, player);
+        let total: i32 = components.iter().map(|(_, score)| score).sum();
+
+        println!("Evaluation for {:?}:", player);
+        for (name, score) in components.iter() {
+            println!("{:<14} {:>+6}", format!("{}:", name), score);
+        }
+        println!("{:<14} {:>+6}", "Total:", total);
+    }
+
+    pub fn draw_board(&self) {
+        let mut stdout = io::stdout();
+        self.board
+            .write_board_with(&mut stdout, &self.render_options)
+            .expect("IO error");
+
+        println!();
+    }
+
+    /// Prints pawn legend, unless disabled with `RenderOptions::show_legend`.
+    pub fn draw_info(&self) {
+        if !self.render_options.show_legend {
+            return;
+        }
+
+        println!("Note that your terminal may change pawn and board apperance, here is reference:");
+
+        self.board.draw_info();
+
+        println!();
+    }
+}
+
+/// Move rating by score lost to the best move, see `Game::evaluate_move_quality`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum MoveQuality {
+    BestMove,
+    // Loss of 1 to 50 centipawns
+    GoodMove(i32),
+    // Loss of 51 to 150 centipawns
+    Inaccuracy(i32),
+    // Loss of 151 to 300 centipawns
+    Mistake(i32),
+    // Loss above 300 centipawns
+    Blunder(i32),
+}
+
+impl MoveQuality {
+    pub fn from_centipawn_loss(loss: i32) -> MoveQuality {
+        match loss {
+            i32::MIN..=0 => MoveQuality::BestMove,
+            1..=50 => MoveQuality::GoodMove(loss),
+            51..=150 => MoveQuality::Inaccuracy(loss),
+            151..=300 => MoveQuality::Mistake(loss),
+            _ => MoveQuality::Blunder(loss),
+        }
+    }
+}
+
+impl fmt::Display for MoveQuality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveQuality::BestMove => write!(f, "Best move!"),
+            MoveQuality::GoodMove(loss) => write!(f, "Good move! (-{}cp)", loss),
+            MoveQuality::Inaccuracy(loss) => write!(f, "Inaccuracy (-{}cp)", loss),
+            MoveQuality::Mistake(loss) => write!(f, "Mistake (-{}cp)", loss),
+            MoveQuality::Blunder(loss) => write!(f, "Blunder (-{}cp)", loss),
+        }
+    }
+}
+
+/// Game result, finished games keep the number of plies played.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum GameResult {
+    WhiteWon(u32),
+    BlackWon(u32),
+    Draw(DrawReason, u32),
+    // Game stopped by `Game::play_game_to_completion` ply limit
+    MaxPliesReached,
+    Ongoing,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum DrawReason {
+    // Player to move has pawns, but can't move them, see `RulesConfig::no_moves_is_draw`
+    NoMoves,
+    // Same position with the same player to move occurred three times, claimed
+    ThreefoldRepetition,
+    // `RulesConfig::draw_ply_limit` plies without captures or man moves, claimed
+    InactivityLimit,
+    // Only a single king is left on each side, claimed
+    InsufficientMaterial,
+}
+
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawReason::NoMoves => write!(f, "no legal moves"),
+            DrawReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+            DrawReason::InactivityLimit => write!(f, "inactivity limit"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+        }
+    }
+}
+
+/// Game data for external tools, created by `Game::to_game_record`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+struct GameRecord {
+    white_player: Option<String>,
+    black_player: Option<String>,
+    date: Option<String>,
+    event: Option<String>,
+    result: GameResult,
+    moves: Vec<MoveRecord>,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+struct MoveRecord {
+    // Move in `Game::make_move` notation
+    move_notation: String,
+    // Time spent on the move
+    time_ms: Option<u64>,
+    // Engine evaluation after the move
+    evaluation: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CheckersError {
+    // The game has already ended
+    GameOver,
+    // Move description couldn't be parsed, with the reason
+    InvalidNotation(&'static str),
+    // Index points outside of the board
+    OutsideBoard,
+    // There is no pawn on the move source tile
+    EmptySource,
+    // Pawn can't make the move
+    IllegalMove,
+    // Recorded move couldn't be made, `move_number` is 1-based
+    InvalidMove {
+        move_number: usize,
+        reason: String,
+    },
+    // FEN couldn't be parsed, with the reason
+    InvalidFen(&'static str),
+    // Binary board couldn't be decoded, with the reason
+    #[cfg(test)]
+    InvalidBytes(&'static str),
+    // Requested ply is outside of the recorded game
+    CannotReachPly(usize),
+    // Starting setup doesn't fit on the board, or the board height is odd
+    InvalidBoardSize {
+        height: usize,
+        width: usize,
+    },
+    // Position rejected by `Game::validate_position`
+    InvalidPosition(PositionError),
+    // Board side is longer than a `Board::serialize_to_bytes` header byte holds
+    #[cfg(test)]
+    BoardTooBigToSerialize {
+        height: usize,
+        width: usize,
+    },
+    // Draw claimed without any `Game::can_draw_be_claimed` reason
+    NoDrawToClaim,
+}
+
+impl fmt::Display for CheckersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckersError::GameOver => write!(f, "The game has already ended"),
+            CheckersError::InvalidNotation(reason) => write!(f, "{}", reason),
+            CheckersError::OutsideBoard => write!(f, "Index outside of board"),
+            CheckersError::EmptySource => write!(f, "Source is an empty tile"),
+            CheckersError::IllegalMove => write!(f, "Illegal move"),
+            CheckersError::InvalidMove {
+                move_number,
+                reason,
+            } => write!(f, "Invalid move {}: {}", move_number, reason),
+            CheckersError::InvalidFen(reason) => write!(f, "Invalid FEN: {}", reason),
+            #[cfg(test)]
+            CheckersError::InvalidBytes(reason) => write!(f, "Invalid board bytes: {}", reason),
+            CheckersError::InvalidPosition(error) => write!(f, "Invalid position: {}", error),
+            CheckersError::CannotReachPly(ply) => write!(f, "Can't reach ply {}", ply),
+            CheckersError::InvalidBoardSize { height, width } => write!(
+                f,
+                "Invalid board size {}x{}, height has to be even and both sizes at least {}",
+                height, width, MIN_BOARD_SIZE
+            ),
+            #[cfg(test)]
+            CheckersError::BoardTooBigToSerialize { height, width } => write!(
+                f,
+                "Board {}x{} is too big to serialize, sizes can be at most {}",
+                height,
+                width,
+                u8::MAX
+            ),
+            CheckersError::NoDrawToClaim => write!(f, "Draw can't be claimed"),
+        }
+    }
+}
+
+/// Reason why position is unreachable, see `Game::validate_position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PositionError {
+    // Player has more pawns than at the start
+    TooManyPieces(Player),
+    // Player has more kings than men it lost
+    TooManyKings(Player),
+    // Pawn stands on a light tile, index is from white player perspective
+    PieceOnLightSquare(Index),
+    // Man stands on its last row, index is from white player perspective
+    UnpromotedMan(Index),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionError::TooManyPieces(player) => {
+                write!(f, "{:?} has more pawns than at the start", player)
+            }
+            PositionError::TooManyKings(player) => {
+                write!(f, "{:?} has more kings than men it lost", player)
+            }
+            PositionError::PieceOnLightSquare(index) => {
+                write!(f, "Pawn on light tile ({}, {})", index.x, index.y)
+            }
+            PositionError::UnpromotedMan(index) => {
+                write!(
+                    f,
+                    "Unpromoted man on the last row ({}, {})",
+                    index.x, index.y
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Move {
+    // Source tile index
+    source: Index,
+    // Landing tiles of a multi capture before the target, empty if not given
+    path: Vec<Index>,
+    // Target tile index
+    target: Index,
+}
+
+impl Move {
+    pub fn new(source: Index, target: Index) -> Move {
+        Move {
+            source,
+            path: Vec::new(),
+            target,
+        }
+    }
+
+    /// Same move seen from the other player perspective, every index is mirrored,
+    /// including the path of multi captures.
+    pub fn reversed(&self, board: &Board) -> Move {
+        Move {
+            source: board.reverse_index(&self.source),
+            path: self
+                .path
+                .iter()
+                .map(|index| board.reverse_index(index))
+                .collect(),
+            target: board.reverse_index(&self.target),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum SquareColor {
+    Light,
+    Dark,
+}
+
+impl SquareColor {
+    pub fn is_dark(&self) -> bool {
+        *self == SquareColor::Dark
+    }
+
+    pub fn is_light(&self) -> bool {
+        *self == SquareColor::Light
+    }
+}
+
+// Change to isize to enable simpler Index math
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Index {
+    // Player board orientation that the move is indexed from
+    orientation: Player,
+    // Horizontal index, 0-based
+    x: usize,
+    // Horizontal index, 0-based
+    y: usize,
+}
+
+/// Indices are ordered by orientation first, then row by row from the top-left corner
+/// of their orientation.
+impl Ord for Index {
+    fn cmp(&self, other: &Index) -> Ordering {
+        (self.orientation, self.y, self.x).cmp(&(other.orientation, other.y, other.x))
+    }
+}
+
+impl PartialOrd for Index {
+    fn partial_cmp(&self, other: &Index) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Index {
+    /// Create new index, x is horizontal index, y is vertical. Indeces are 0-based
+    pub fn new(x: usize, y: usize, orientation: Player) -> Index {
+        Index { x, y, orientation }
+    }
+
+    /// Index of dark tile number `n` in standard numbering, counting from 1
+    /// row by row, from the top left of white player perspective.
+    #[cfg(test)]
+    pub fn from_square_number(
+        n: usize,
+        board: &Board,
+        orientation: Player,
+    ) -> Result<Index, CheckersError> {
+        let mut remaining = n.checked_sub(1).ok_or(CheckersError::OutsideBoard)?;
+
+        for y in 0..board.height() {
+            let (first, dark_tiles) = board.dark_tiles_in_row(y);
+            if remaining < dark_tiles {
+                let index = Index::new(first + 2 * remaining, y, Player::White);
+
+                return Ok(match orientation {
+                    Player::White => index,
+                    Player::Black => board.reverse_index(&index),
+                });
+            }
+            remaining -= dark_tiles;
+        }
+
+        Err(CheckersError::OutsideBoard)
+    }
+
+    /// Standard number of the tile, see `Index::from_square_number`.
+    #[cfg(test)]
+    pub fn to_square_number(self, board: &Board) -> Result<usize, CheckersError> {
+        if !board.validate_index(self) {
+            return Err(CheckersError::OutsideBoard);
+        }
+
+        let index = board.to_white_index(self);
+        let (first, _) = board.dark_tiles_in_row(index.y);
+        if index.x % 2 != first {
+            return Err(CheckersError::InvalidNotation("Light tiles have no number"));
+        }
+
+        let previous_rows: usize = (0..index.y).map(|y| board.dark_tiles_in_row(y).1).sum();
+
+        Ok(previous_rows + (index.x - first) / 2 + 1)
+    }
+
+    /// Color of the tile in the index orientation, see also `Board::is_dark_square`.
+    pub fn square_color(&self) -> SquareColor {
+        if (self.x + self.y) % 2 == 1 {
+            SquareColor::Dark
+        } else {
+            SquareColor::Light
+        }
+    }
+
+    /// Creates new index moved by (x, y)
+    ///
+    /// Coordinates which would overflow are off the board, so they give `None` too.
+    // change `Option` to `Result`
+    pub fn translate(&self, x: isize, y: isize) -> Option<Index> {
+        let offset = |coordinate: usize, delta: isize| {
+            isize::try_from(coordinate)
+                .ok()
+                .and_then(|coordinate| coordinate.checked_add(delta))
+                .and_then(|coordinate| usize::try_from(coordinate).ok())
+        };
+        let x_new = offset(self.x, x);
+        let y_new = offset(self.y, y);
+
+        if x_new.is_none() || y_new.is_none() {
+            if trace_enabled() {
+                eprintln!("[trace] translate({:?}, {}, {}) = None", self, x, y);
+            }
+            return None;
+        }
+
+        let index_translated = Index::new(x_new.unwrap(), y_new.unwrap(), self.orientation);
+
+        if trace_enabled() {
+            eprintln!(
+                "[trace] translate({:?}, {}, {}) = {:?}",
+                self, x, y, index_translated
+            );
+        }
+
+        Some(index_translated)
+    }
+}
+
+// TODO: move description doesn't support multi captures yet
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MoveType {
+    Move(Index),
+    Capture {
+        target_index: Index,
+        captured_index: Index,
+    },
+    MultiCapture(Vec<Index>),
+    KingMove(Index),
+    KingCapture {
+        target_index: Index,
+        captured_index: Index,
+    },
+    KingMultiCapture(Vec<Index>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AvailableMove {
+    source: Index,
+    move_type: MoveType,
+}
+
+impl AvailableMove {
+    pub fn new(source: Index, move_type: MoveType) -> AvailableMove {
+        AvailableMove { source, move_type }
+    }
+
+    pub fn source(&self) -> Index {
+        self.source
+    }
+
+    pub fn move_type(&self) -> &MoveType {
+        &self.move_type
+    }
+
+    pub fn is_capture(&self) -> bool {
+        !matches!(self.move_type, MoveType::Move(_) | MoveType::KingMove(_))
+    }
+
+    /// Number of pawns captured by the move.
+    pub fn capture_count(&self) -> usize {
+        match &self.move_type {
+            MoveType::Move(_) | MoveType::KingMove(_) => 0,
+            MoveType::Capture { .. } | MoveType::KingCapture { .. } => 1,
+            MoveType::MultiCapture(path) | MoveType::KingMultiCapture(path) => path.len(),
+        }
+    }
+
+    /// Tiles where the pawn lands, in move order, the last one is `target`.
+    pub fn path(&self) -> Vec<Index> {
+        match &self.move_type {
+            MoveType::MultiCapture(path) | MoveType::KingMultiCapture(path) => path.clone(),
+            _ => vec![self.target()],
+        }
+    }
+
+    /// Index where the pawn ends up after the move.
+    pub fn target(&self) -> Index {
+        match &self.move_type {
+            MoveType::Move(index) | MoveType::KingMove(index) => *index,
+            MoveType::Capture { target_index, .. } | MoveType::KingCapture { target_index, .. } => {
+                *target_index
+            }
+            MoveType::MultiCapture(path) | MoveType::KingMultiCapture(path) => {
+                *path.last().expect(INTERNAL_ERROR_MESSAGE)
+            }
+        }
+    }
+}
+
+/// Weights of position evaluation components.
+#[derive(Debug, Clone, PartialEq)]
+struct EvalWeights {
+    man_value: i32,
+    king_value: i32,
+    // Subtracted from value of a pawn that can be captured
+    threatened_pawn_penalty: i32,
+    // Multiplier of king coordinates variance
+    king_spread_bonus: i32,
+    // Added for every pawn without friendly neighbours, negative
+    isolation_penalty: i32,
+    // Added for every bridge, see `Board::bridge_count`
+    bridge_bonus: i32,
+    // Added for every formation, see `Board::detect_defense_formation`
+    formation_bonus: HashMap<FormationName, i32>,
+    // Multiplier of tempo difference, applied in the opening only
+    opening_tempo_weight: i32,
+    // Added for every king in a double corner, where a weaker side can hold a draw
+    double_corner_bonus: i32,
+    // Added for every enemy man about to promote, see `Board::count_promotion_threats`,
+    // negative
+    promotion_threat_penalty: i32,
+    // Added for every king without a safe move, see `Board::count_safe_king_moves`, negative
+    trapped_king_penalty: i32,
+}
+
+impl EvalWeights {
+    /// Weights tuned for a game phase, midgame uses the default weights.
+    ///
+    /// Bridges guard the back rank in the opening, king placement decides the endgame.
+    pub fn for_phase(phase: GamePhase) -> EvalWeights {
+        match phase {
+            GamePhase::Opening => EvalWeights {
+                king_spread_bonus: 1,
+                isolation_penalty: -5,
+                bridge_bonus: 10,
+                opening_tempo_weight: 2,
+                ..EvalWeights::default()
+            },
+            GamePhase::Midgame => EvalWeights::default(),
+            GamePhase::Endgame => EvalWeights {
+                king_spread_bonus: 4,
+                isolation_penalty: -15,
+                bridge_bonus: 0,
+                double_corner_bonus: 10,
+                ..EvalWeights::default()
+            },
+        }
+    }
+}
+
+impl Default for EvalWeights {
+    fn default() -> EvalWeights {
+        EvalWeights {
+            man_value: 100,
+            king_value: 300,
+            threatened_pawn_penalty: 30,
+            king_spread_bonus: 2,
+            isolation_penalty: -10,
+            bridge_bonus: 5,
+            formation_bonus: HashMap::from([
+                (FormationName::Triangle, 5),
+                (FormationName::Diamond, 8),
+                (FormationName::Pyramid, 15),
+                (FormationName::Phalanx, 4),
+                (FormationName::Dyke, 6),
+            ]),
+            opening_tempo_weight: 0,
+            double_corner_bonus: 0,
+            promotion_threat_penalty: -50,
+            trapped_king_penalty: -150,
+        }
+    }
+}
+
+/// Minimax game tree search with alpha-beta pruning.
+///
+/// Scores are given from the perspective of the player to move. Won positions score
+/// `WIN_SCORE` minus number of moves needed, so faster wins are preferred.
+#[derive(Debug)]
+struct Minimax {
+    depth: usize,
+    // Weights of every `GamePhase`, in declaration order
+    weights: [EvalWeights; 3],
+    // Accumulated over all searches of this instance
+    quiesce_stats: Cell<QuiesceStats>,
+    // Null move pruning in the current search, off when the root is zugzwang
+    null_move_pruning: Cell<bool>,
+    // Set while searching after a passed move, so passes aren't nested
+    in_null_move: Cell<bool>,
+}
+
+/// Quiescence search counters, see `Minimax::quiesce_stats`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct QuiesceStats {
+    // Positions visited by quiescence search
+    qnodes_visited: u64,
+    // Quiescence positions evaluated right away, because they were quiet
+    horizon_hits: u64,
+}
+
+impl Minimax {
+    /// Search evaluating positions with `EvalWeights::for_phase` weights.
+    pub fn new(depth: usize) -> Minimax {
+        Minimax {
+            depth,
+            weights: [
+                EvalWeights::for_phase(GamePhase::Opening),
+                EvalWeights::for_phase(GamePhase::Midgame),
+                EvalWeights::for_phase(GamePhase::Endgame),
+            ],
+            quiesce_stats: Cell::new(QuiesceStats::default()),
+            null_move_pruning: Cell::new(false),
+            in_null_move: Cell::new(false),
+        }
+    }
+
+    /// Search evaluating positions with the same `weights` in every game phase.
+    pub fn with_weights(depth: usize, weights: EvalWeights) -> Minimax {
+        Minimax {
+            weights: [weights.clone(), weights.clone(), weights],
+            ..Minimax::new(depth)
+        }
+    }
+
+    /// Evaluation weights of the position.
+    pub fn weights_for(&self, game: &Game) -> &EvalWeights {
+        &self.weights[game.phase() as usize]
+    }
+
+    #[cfg(test)]
+    pub fn quiesce_stats(&self) -> QuiesceStats {
+        self.quiesce_stats.get()
+    }
+
+    /// Finds the best move for the player to move, together with its score.
+    ///
+    /// Returns `None` when the game has ended or the player can't move.
+    pub fn best_move(&self, game: &Game) -> Option<(AvailableMove, i32)> {
+        let player = match game.state {
+            GameState::Turn(player) => player,
+            _ => return None,
+        };
+
+        self.set_up_null_move_pruning(game);
+
+        let mut best: Option<(AvailableMove, i32)> = None;
+        let mut alpha = -WIN_SCORE;
+        for available_move in Minimax::order_moves(game, game.legal_moves_for(player)) {
+            let mut child = game.clone();
+            child.apply_move(&available_move);
+
+            let score = -self.search(
+                &child,
+                get_enemy(player),
+                self.depth.saturating_sub(1),
+                1,
+                -WIN_SCORE,
+                -alpha,
+            );
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some((available_move, score));
+            }
+        }
+
+        best
+    }
+
+    /// Exact score of `available_move` for the player to move, searched at the same
+    /// depth as `best_move`.
+    pub fn score_move(&self, game: &Game, available_move: &AvailableMove) -> i32 {
+        let player = match game.state {
+            GameState::Turn(player) => player,
+            _ => return 0,
+        };
+
+        self.set_up_null_move_pruning(game);
+
+        let mut child = game.clone();
+        child.apply_move(available_move);
+
+        -self.search(
+            &child,
+            get_enemy(player),
+            self.depth.saturating_sub(1),
+            1,
+            -WIN_SCORE,
+            WIN_SCORE,
+        )
+    }
+
+    /// Enables null move pruning for a search from `game`, unless the search is too
+    /// shallow to use it or `Game::zugzwang_check` finds the root is zugzwang.
+    fn set_up_null_move_pruning(&self, game: &Game) {
+        let enabled =
+            self.depth > NULL_MOVE_REDUCTION + 1 && !game.zugzwang_check(ZUGZWANG_CHECK_DEPTH);
+
+        self.null_move_pruning.set(enabled);
+    }
+
+    /// Whether `search` can try passing the turn in `game`, see `set_up_null_move_pruning`.
+    ///
+    /// Passing is skipped with pending captures, near win scores and in likely zugzwang
+    /// positions, see `Board::is_likely_zugzwang`, where passing overrates the position.
+    fn can_try_null_move(
+        &self,
+        game: &Game,
+        player: Player,
+        depth: usize,
+        ply: usize,
+        beta: i32,
+    ) -> bool {
+        self.null_move_pruning.get()
+            && !self.in_null_move.get()
+            && ply > 0
+            && depth > NULL_MOVE_REDUCTION
+            && !Minimax::is_win_score(beta.abs())
+            && game.is_quiet()
+            && !game.board.is_likely_zugzwang(player)
+    }
+
+    pub fn is_win_score(score: i32) -> bool {
+        score > WIN_SCORE - MAX_SEARCH_PLIES
+    }
+
+    /// Depth-to-mate search, returns number of plies `player` needs to win if it can be
+    /// forced within `max_plies`.
+    ///
+    /// `player` picks the fastest win, opponent picks the longest defence.
+    pub fn plies_to_win(game: &Game, player: Player, max_plies: usize) -> Option<usize> {
+        let to_move = match game.state {
+            GameState::Won(winner) if winner == player => return Some(0),
+            GameState::Won(_) | GameState::Draw(_) => return None,
+            GameState::Turn(to_move) => to_move,
+        };
+
+        if max_plies == 0 {
+            return None;
+        }
+
+        let mut results = game
+            .legal_moves_for(to_move)
+            .into_iter()
+            .map(|available_move| {
+                let mut child = game.clone();
+                child.apply_move(&available_move);
+
+                Minimax::plies_to_win(&child, player, max_plies - 1).map(|plies| plies + 1)
+            });
+
+        if to_move == player {
+            results.flatten().min()
+        } else {
+            // every defence has to lose, otherwise there is no forced win
+            results.try_fold(0, |longest, plies| plies.map(|plies| plies.max(longest)))
+        }
+    }
+
+    fn search(
+        &self,
+        game: &Game,
+        player: Player,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        match game.state {
+            GameState::Won(winner) if winner == player => return WIN_SCORE - ply as i32,
+            GameState::Won(_) => return -(WIN_SCORE - ply as i32),
+            GameState::Draw(_) => return 0,
+            GameState::Turn(_) => (),
+        }
+
+        if game.is_theoretical_draw() {
+            return 0;
+        }
+
+        if depth == 0 {
+            return self.quiesce(game, player, ply, alpha, beta);
+        }
+
+        if self.can_try_null_move(game, player, depth, ply, beta) {
+            let mut passed = game.clone();
+            passed.apply_null_move();
+
+            self.in_null_move.set(true);
+            let null_score = -self.search(
+                &passed,
+                get_enemy(player),
+                depth - 1 - NULL_MOVE_REDUCTION,
+                ply + 1,
+                -beta,
+                -beta + 1,
+            );
+            self.in_null_move.set(false);
+
+            // even passing fails high, so some move is assumed to do at least as well
+            if null_score >= beta {
+                return beta;
+            }
+        }
+
+        for available_move in Minimax::order_moves(game, game.legal_moves_for(player)) {
+            let mut child = game.clone();
+            child.apply_move(&available_move);
+
+            let score = -self.search(&child, get_enemy(player), depth - 1, ply + 1, -beta, -alpha);
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    /// Searches captures only past the search horizon, so positions with pending
+    /// captures aren't misjudged by the static evaluation.
+    fn quiesce(&self, game: &Game, player: Player, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        let mut stats = self.quiesce_stats.get();
+        stats.qnodes_visited += 1;
+
+        match game.state {
+            GameState::Won(winner) if winner == player => return WIN_SCORE - ply as i32,
+            GameState::Won(_) => return -(WIN_SCORE - ply as i32),
+            GameState::Draw(_) => return 0,
+            GameState::Turn(_) => (),
+        }
+
+        if game.is_quiet() {
+            stats.horizon_hits += 1;
+            self.quiesce_stats.set(stats);
+
+            return self.evaluate(game, player);
+        }
+        self.quiesce_stats.set(stats);
+
+        // player doesn't have to capture, so it can keep the static evaluation
+        let stand_pat = self.evaluate(game, player);
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        for available_move in game.legal_captures_for(player) {
+            let mut child = game.clone();
+            child.apply_move(&available_move);
+
+            let score = -self.quiesce(&child, get_enemy(player), ply + 1, -beta, -alpha);
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    /// Puts fork moves first and moves creating skewer threats next,
+    /// so alpha-beta can cut off the remaining moves sooner.
+    ///
+    /// Within each group material winning captures go first and even trades next.
+    fn order_moves(game: &Game, moves: Vec<AvailableMove>) -> Vec<AvailableMove> {
+        let player = match game.state {
+            GameState::Turn(player) => player,
+            _ => return moves,
+        };
+        let attacked_before = game.attacked_pieces_count(get_enemy(player));
+        // only kings create skewers, so skip looking for them without kings
+        let has_kings = game
+            .board
+            .get_pieces(player)
+            .iter()
+            .any(|index| game.board.get_tile(*index).is_ok_and(|tile| tile.is_king()));
+
+        let mut ranked_moves: Vec<(usize, AvailableMove)> = moves
+            .into_iter()
+            .map(|available_move| {
+                // both signals are read from the same resulting position
+                let mut child = game.clone();
+                child.apply_move(&available_move);
+
+                if child.is_fork_of(player, attacked_before) {
+                    (0, available_move)
+                } else if has_kings && !child.get_skewer_threats(player).is_empty() {
+                    (1, available_move)
+                } else {
+                    (2, available_move)
+                }
+            })
+            .collect();
+
+        // stable sort keeps generation order within the same rank
+        ranked_moves.sort_by_key(|(rank, available_move)| {
+            let trade_rank = if available_move.is_capture() {
+                match game.capture_material_gain(available_move).cmp(&0) {
+                    Ordering::Greater => 0,
+                    Ordering::Equal => 1,
+                    Ordering::Less => 2,
+                }
+            } else {
+                2
+            };
+
+            (*rank, trade_rank)
+        });
+
+        ranked_moves
+            .into_iter()
+            .map(|(_, available_move)| available_move)
+            .collect()
+    }
+
+    /// Position score from `player` perspective.
+    fn evaluate(&self, game: &Game, player: Player) -> i32 {
+        self.evaluate_components(game, player)
+            .iter()
+            .map(|(_, score)| score)
+            .sum()
+    }
+
+    /// Named evaluation components from `player` perspective, they sum up to `evaluate`.
+    pub fn evaluate_components(&self, game: &Game, player: Player) -> Vec<(&'static str, i32)> {
+        vec![
+            ("Material", self.evaluate_material(game, player)),
+            ("Threats", self.evaluate_threats(game, player)),
+            ("King spread", self.evaluate_king_spread(game, player)),
+            ("Isolation", self.evaluate_isolation(game, player)),
+            ("Bridges", self.evaluate_bridges(game, player)),
+            ("Formations", self.evaluate_formations(game, player)),
+            ("Tempo", self.evaluate_tempo(game, player)),
+            ("Corners", self.evaluate_corners(game, player)),
+            ("Promotion", self.evaluate_promotion_threats(game, player)),
+            ("Trapped kings", self.evaluate_trapped_kings(game, player)),
+        ]
+    }
+
+    /// Penalty for kings that can't step anywhere safe, bonus for trapped enemy kings.
+    pub fn evaluate_trapped_kings(&self, game: &Game, player: Player) -> i32 {
+        let board = &game.board;
+        let attack_map = game.get_attack_map();
+        let trapped_kings = |player| {
+            board
+                .get_pieces(player)
+                .into_iter()
+                .filter(|index| {
+                    board.get_tile(*index).is_ok_and(|tile| tile.is_king())
+                        && board.count_safe_king_moves_with(*index, player, attack_map) == 0
+                })
+                .count() as i32
+        };
+
+        (trapped_kings(player) - trapped_kings(get_enemy(player)))
+            * self.weights_for(game).trapped_king_penalty
+    }
+
+    /// Penalty for enemy men about to promote, bonus for own ones.
+    pub fn evaluate_promotion_threats(&self, game: &Game, player: Player) -> i32 {
+        if !game.board.has_promotion_threat(player)
+            && !game.board.has_promotion_threat(get_enemy(player))
+        {
+            return 0;
+        }
+
+        let threats = |player| game.board.count_promotion_threats(player) as i32;
+
+        (threats(get_enemy(player)) - threats(player))
+            * self.weights_for(game).promotion_threat_penalty
+    }
+
+    /// Bonus for kings sheltered in a double corner.
+    pub fn evaluate_corners(&self, game: &Game, player: Player) -> i32 {
+        let board = &game.board;
+        let double_corner_kings = |player| {
+            board
+                .get_pieces(player)
+                .into_iter()
+                .filter(|index| {
+                    board.get_tile(*index).is_ok_and(|tile| tile.is_king())
+                        && board.corner_kind(*index) == Some(CornerKind::DoubleCorner)
+                })
+                .count() as i32
+        };
+
+        (double_corner_kings(player) - double_corner_kings(get_enemy(player)))
+            * self.weights_for(game).double_corner_bonus
+    }
+
+    /// Material balance from `player` perspective.
+    pub fn evaluate_material(&self, game: &Game, player: Player) -> i32 {
+        let score = game.board.compute_material_score(self.weights_for(game));
+
+        match player {
+            Player::White => score,
+            Player::Black => -score,
+        }
+    }
+
+    /// Penalty for `player` pawns under attack, bonus for attacked enemy pawns.
+    pub fn evaluate_threats(&self, game: &Game, player: Player) -> i32 {
+        let enemy = get_enemy(player);
+        let attack_map = game.get_attack_map();
+
+        (game
+            .board
+            .get_pieces_under_attack_with(enemy, attack_map)
+            .len() as i32
+            - game
+                .board
+                .get_pieces_under_attack_with(player, attack_map)
+                .len() as i32)
+            * self.weights_for(game).threatened_pawn_penalty
+    }
+
+    pub fn evaluate_king_spread(&self, game: &Game, player: Player) -> i32 {
+        let board = &game.board;
+        let weights = self.weights_for(game);
+
+        board.king_distribution_score(player, weights)
+            - board.king_distribution_score(get_enemy(player), weights)
+    }
+
+    pub fn evaluate_isolation(&self, game: &Game, player: Player) -> i32 {
+        let board = &game.board;
+
+        (board.get_isolated_pieces(player).len() as i32
+            - board.get_isolated_pieces(get_enemy(player)).len() as i32)
+            * self.weights_for(game).isolation_penalty
+    }
+
+    pub fn evaluate_bridges(&self, game: &Game, player: Player) -> i32 {
+        let board = &game.board;
+
+        (board.bridge_count(player) as i32 - board.bridge_count(get_enemy(player)) as i32)
+            * self.weights_for(game).bridge_bonus
+    }
+
+    /// Bonus for advancing men faster than the enemy, in the opening only.
+    pub fn evaluate_tempo(&self, game: &Game, player: Player) -> i32 {
+        if game.phase() != GamePhase::Opening {
+            return 0;
+        }
+
+        let score = game.tempo_difference() * self.weights_for(game).opening_tempo_weight;
+
+        match player {
+            Player::White => score,
+            Player::Black => -score,
+        }
+    }
+
+    pub fn evaluate_formations(&self, game: &Game, player: Player) -> i32 {
+        let formation_bonus = &self.weights_for(game).formation_bonus;
+        let score = |player| -> i32 {
+            game.board
+                .detect_defense_formation(player)
+                .iter()
+                .map(|name| formation_bonus.get(name).copied().unwrap_or(0))
+                .sum()
+        };
+
+        score(player) - score(get_enemy(player))
+    }
+}
+
+/// AI player settings.
+#[derive(Debug, Clone, PartialEq)]
+struct AIConfig {
+    // Search depth in plies
+    depth: usize,
+    // Fixed evaluation weights, weights of the game phase when `None`
+    weights: Option<EvalWeights>,
+}
+
+impl AIConfig {
+    pub fn new(depth: usize) -> AIConfig {
+        AIConfig {
+            depth,
+            weights: None,
+        }
+    }
+
+    pub fn minimax(&self) -> Minimax {
+        match &self.weights {
+            Some(weights) => Minimax::with_weights(self.depth, weights.clone()),
+            None => Minimax::new(self.depth),
+        }
+    }
+}
+
+/// AI vs AI games from the starting position, for benchmarking AI settings.
+#[derive(Debug, Clone)]
+struct SelfPlay {
+    white_ai: AIConfig,
+    black_ai: AIConfig,
+    rules: RulesConfig,
+    // Games still running after this many plies are stopped
+    max_plies: usize,
+}
+
+impl SelfPlay {
+    pub fn new(white_ai: AIConfig, black_ai: AIConfig, max_plies: usize) -> SelfPlay {
+        SelfPlay {
+            white_ai,
+            black_ai,
+            rules: RulesConfig::default(),
+            max_plies,
+        }
+    }
+
+    /// Plays a single game, its record holds the result.
+    pub fn run(&self) -> GameRecord {
+        let mut game = Game::with_rules(self.rules);
+        let result = game.play_game_to_completion(&self.white_ai, &self.black_ai, self.max_plies);
+
+        GameRecord {
+            result,
+            ..game.to_game_record()
+        }
+    }
+
+    /// Plays `games` games one after another.
+    ///
+    /// Search is deterministic, so all games are the same, batches measure search speed.
+    pub fn run_batch(&self, games: usize) -> Vec<GameRecord> {
+        (0..games).map(|_| self.run()).collect()
+    }
+}
+
+/// Settings of a `Checkers` session, kept between games.
+#[derive(Debug, Clone, PartialEq)]
+struct CheckersConfig {
+    white_player: String,
+    black_player: String,
+    rules: RulesConfig,
+    render_options: RenderOptions,
+    // Computer opponent, human vs human when `None`
+    ai: Option<AIConfig>,
+    // Games are untimed when `None`
+    time_control: Option<TimeControl>,
+}
+
+impl Default for CheckersConfig {
+    fn default() -> CheckersConfig {
+        CheckersConfig {
+            white_player: String::from("Human"),
+            black_player: String::from("Human"),
+            rules: RulesConfig::default(),
+            render_options: RenderOptions::default(),
+            ai: None,
+            time_control: None,
+        }
+    }
+}
+
+/// Thinking time of each player for the whole game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct TimeControl {
+    initial: Duration,
+    // Added to the remaining time of a player after each of their moves
+    increment: Duration,
+}
+
+impl TimeControl {
+    pub fn new(initial: Duration, increment: Duration) -> TimeControl {
+        TimeControl { initial, increment }
+    }
+
+    /// Parses `<minutes>[+<increment seconds>]`, ex. `5+3`.
+    pub fn parse(description: &str) -> Result<TimeControl, String> {
+        let invalid = || format!("Invalid time control {}", description);
+        let mut parts = description.splitn(2, '+');
+
+        let minutes = parts
+            .next()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .ok_or_else(invalid)?;
+        let increment = match parts.next() {
+            Some(seconds) => seconds.parse::<u64>().map_err(|_| invalid())?,
+            None => 0,
+        };
+
+        Ok(TimeControl::new(
+            Duration::from_secs(minutes * 60),
+            Duration::from_secs(increment),
+        ))
+    }
+}
+
+/// Remaining thinking time of both players, see `TimeControl`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Clock {
+    time_control: TimeControl,
+    white_remaining: Duration,
+    black_remaining: Duration,
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl) -> Clock {
+        Clock {
+            time_control,
+            white_remaining: time_control.initial,
+            black_remaining: time_control.initial,
+        }
+    }
+
+    pub fn remaining(&self, player: Player) -> Duration {
+        match player {
+            Player::White => self.white_remaining,
+            Player::Black => self.black_remaining,
+        }
+    }
+
+    /// Charges `player` for a move that took `elapsed`.
+    ///
+    /// The increment is added only if the player still had time left.
+    pub fn record_move(&mut self, player: Player, elapsed: Duration) {
+        let increment = self.time_control.increment;
+        let remaining = match player {
+            Player::White => &mut self.white_remaining,
+            Player::Black => &mut self.black_remaining,
+        };
+
+        *remaining = remaining.saturating_sub(elapsed);
+        if !remaining.is_zero() {
+            *remaining += increment;
+        }
+    }
+
+    /// Whether `player` has run out of time.
+    pub fn is_flagged(&self, player: Player) -> bool {
+        self.remaining(player).is_zero()
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = |remaining: Duration| {
+            let seconds = remaining.as_secs();
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        };
+
+        write!(
+            f,
+            "White {} | Black {}",
+            format(self.white_remaining),
+            format(self.black_remaining)
+        )
+    }
+}
+
+/// Playing session, runs games one after another with the same configuration.
+#[derive(Debug, Clone)]
+struct Checkers {
+    config: CheckersConfig,
+    game: Game,
+    // Clock of the current game, `None` for untimed games
+    clock: Option<Clock>,
+    // Records of games replaced by `start_new_game`, oldest first
+    history: Vec<GameRecord>,
+}
+
+impl Checkers {
+    pub fn new(config: CheckersConfig) -> Checkers {
+        let game = Checkers::configured_game(&config, Game::with_rules(config.rules));
+
+        Checkers {
+            clock: config.time_control.map(Clock::new),
+            config,
+            game,
+            history: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn config(&self) -> &CheckersConfig {
+        &self.config
+    }
+
+    /// Replaces the current game with a new one, the current game is kept in
+    /// `game_history` if any move was made.
+    #[cfg(test)]
+    pub fn start_new_game(&mut self) {
+        self.start_new_game_from(Game::with_rules(self.config.rules));
+    }
+
+    /// Same as `start_new_game`, but continues from `game`, ex. loaded from FEN.
+    ///
+    /// Player names and render options of the session override the ones of `game`.
+    pub fn start_new_game_from(&mut self, game: Game) {
+        let game = Checkers::configured_game(&self.config, game);
+        let finished = std::mem::replace(&mut self.game, game);
+        self.clock = self.config.time_control.map(Clock::new);
+
+        if finished.half_move_number() > 0 {
+            self.history.push(finished.to_game_record());
+        }
+    }
+
+    pub fn current_game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn current_game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Charges `player` for a move of the current game that took `elapsed`.
+    ///
+    /// Player whose time ran out loses the game. Does nothing in untimed games.
+    pub fn record_move_time(&mut self, player: Player, elapsed: Duration) {
+        let clock = match &mut self.clock {
+            Some(clock) => clock,
+            None => return,
+        };
+
+        clock.record_move(player, elapsed);
+        if clock.is_flagged(player) {
+            self.game.state = GameState::Won(get_enemy(player));
+        }
+    }
+
+    /// Records of previous games of the session, oldest first.
+    #[cfg(test)]
+    pub fn game_history(&self) -> &[GameRecord] {
+        &self.history
+    }
+
+    fn configured_game(config: &CheckersConfig, mut game: Game) -> Game {
+        game.set_player_names(config.white_player.clone(), config.black_player.clone());
+        game.render_options = config.render_options;
+
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_squares_on_promotion_row_hold_kings() {
+        let game = Game::from_fen("6/6/6/6/3b2/W5 w").unwrap();
+
+        // used to panic placing a man on the last row
+        for index in game.get_critical_squares(Player::White, 2) {
+            assert!(index.square_color().is_dark());
+            assert_eq!(game.board.get_tile(index), Ok(Tile::Empty));
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn game_from_args_maps_fen_and_player() {
+        let (game, time_control) = game_from_args(&args(&[])).unwrap();
+        assert!(game.same_position(&Game::new()));
+        assert_eq!(time_control, None);
+
+        let (game, _) = game_from_args(&args(&["--fen", "6/6/6/6/3b2/W5 w"])).unwrap();
+        assert_eq!(game.to_fen(), "6/6/6/6/3b2/W5 w");
+
+        let (game, _) =
+            game_from_args(&args(&["--fen", "6/6/6/6/3b2/W5", "--black-to-move"])).unwrap();
+        assert_eq!(game.state, GameState::Turn(Player::Black));
+
+        let (_, time_control) = game_from_args(&args(&["--time", "5+3"])).unwrap();
+        assert_eq!(
+            time_control,
+            Some(TimeControl::new(
+                Duration::from_secs(300),
+                Duration::from_secs(3)
+            ))
+        );
+
+        assert!(game_from_args(&args(&["--fen"])).is_err());
+        assert!(game_from_args(&args(&["--time", "0"])).is_err());
+        assert!(game_from_args(&args(&["--time", "5+x"])).is_err());
+        assert!(game_from_args(&args(&["--unknown"])).is_err());
+    }
+
+    #[test]
+    fn from_fen_rejects_huge_empty_runs() {
+        assert_eq!(
+            Board::from_fen("99999999999999999999"),
+            Err(CheckersError::InvalidFen(
+                "Board width has to be between 1 and 26"
+            ))
+        );
+        assert!(Board::from_fen("27").is_err());
+        assert!(Board::from_fen("26").is_ok());
+    }
+
+    #[test]
+    fn from_fen_rejects_unreachable_positions() {
+        let unpromoted = Game::from_fen("1w4/6/6/6/3b2/W5 w");
+        assert_eq!(
+            unpromoted.err(),
+            Some(CheckersError::InvalidPosition(
+                PositionError::UnpromotedMan(Index::new(1, 0, Player::White))
+            ))
+        );
+
+        let light_square = Game::from_fen("6/6/6/6/3b2/1w4 w");
+        assert_eq!(
+            light_square.err(),
+            Some(CheckersError::InvalidPosition(
+                PositionError::PieceOnLightSquare(Index::new(1, 5, Player::White))
+            ))
+        );
+    }
+
+    #[test]
+    fn promoted_king_next_to_every_man_is_unreachable() {
+        assert!(Game::new().is_position_reachable());
+
+        let board = Board::from_fen("1W4/6/3b2/6/1w1w1w/w1w1w1").unwrap();
+        let game = Game::with_board(board, RulesConfig::default());
+        assert!(!game.is_position_reachable());
+    }
+
+    fn stalemated_game(rules: RulesConfig) -> Game {
+        // white man blocked by black men, jump over C4 is blocked by D3
+        let board = Board::from_fen("6/6/3b2/b1b3/1w4/6").unwrap();
+        let mut game = Game::with_board(board, rules);
+        game.check_game_over();
+
+        game
+    }
+
+    #[test]
+    fn stalemate_is_loss_by_default() {
+        let game = stalemated_game(RulesConfig::default());
+
+        assert_eq!(game.state, GameState::Won(Player::Black));
+    }
+
+    #[test]
+    fn stalemate_is_draw_with_no_moves_is_draw() {
+        let game = stalemated_game(RulesConfig {
+            no_moves_is_draw: true,
+            ..RulesConfig::default()
+        });
+
+        assert_eq!(game.state, GameState::Draw(DrawReason::NoMoves));
+        assert_eq!(game.result(), GameResult::Draw(DrawReason::NoMoves, 0));
+    }
+
+    #[test]
+    fn attack_map_is_cached_until_the_board_changes() {
+        let mut game = Game::from_fen("6/6/6/2b3/1w4/6 w").unwrap();
+
+        let first: *const AttackMap = game.get_attack_map();
+        let second: *const AttackMap = game.get_attack_map();
+        assert_eq!(first, second);
+        assert!(game
+            .get_attack_map()
+            .is_attacked_by(Index::new(1, 4, Player::White), Player::Black));
+
+        // the only black pawn is captured, so nothing is attacked by black anymore
+        game.make_move("B5 D3").unwrap();
+        assert!(!game
+            .get_attack_map()
+            .is_attacked_by(Index::new(1, 4, Player::White), Player::Black));
+    }
+
+    #[test]
+    fn off_board_indices_are_never_attacked() {
+        let attack_map = Board::new(8, 8).compute_attack_map();
+        for player in [Player::White, Player::Black] {
+            for orientation in [Player::White, Player::Black] {
+                assert!(!attack_map.is_attacked_by(Index::new(8, 0, orientation), player));
+                assert!(!attack_map.is_attacked_by(Index::new(0, 8, orientation), player));
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_threats_matches_fresh_attack_map() {
+        let game = Game::from_fen("6/6/6/2b3/1w4/6 w").unwrap();
+        let minimax = Minimax::new(1);
+
+        let threatened = |player| game.board.get_pieces_under_attack(player).len() as i32;
+        let expected = (threatened(Player::Black) - threatened(Player::White))
+            * minimax.weights_for(&game).threatened_pawn_penalty;
+
+        assert_eq!(minimax.evaluate_threats(&game, Player::White), expected);
+    }
+
+    fn game_with_rules(fen: &str, rules: RulesConfig) -> Game {
+        Game::with_board(Board::from_fen(fen).unwrap(), rules)
+    }
+
+    #[test]
+    fn flying_king_attacks_cover_whole_diagonals() {
+        let mut board = Board::new_empty(8, 8);
+        let king = Index::new(3, 4, Player::White);
+        board.set_tile(king, Tile::WhiteKing).unwrap();
+
+        let attacks = board.compute_flying_king_attacks(Player::White);
+        assert_eq!(attacks.len(), 1);
+        assert_eq!(attacks[0].0, king);
+
+        let mut squares = attacks[0].1.clone();
+        squares.sort();
+        let mut expected: Vec<Index> = Direction::all()
+            .iter()
+            .flat_map(|direction| {
+                let (dx, dy) = direction.offset();
+                (1..8).map_while(move |distance| king.translate(dx * distance, dy * distance))
+            })
+            .filter(|index| board.validate_index(*index))
+            .collect();
+        expected.sort();
+        assert_eq!(squares, expected);
+    }
+
+    #[test]
+    fn fork_detection_uses_flying_kings() {
+        let fen = "3W4/8/8/2b3b1/8/8/8/8";
+        let fork_targets = |rules| {
+            let game = game_with_rules(fen, rules);
+            game.get_fork_moves()
+                .iter()
+                .map(|fork| game.board.to_white_index(fork.target()))
+                .collect::<Vec<Index>>()
+        };
+
+        assert!(fork_targets(RulesConfig::pool()).contains(&Index::new(4, 1, Player::White)));
+        assert!(fork_targets(RulesConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn threat_evaluation_uses_flying_kings() {
+        let fen = "8/8/8/8/3b4/8/8/W7";
+        let minimax = Minimax::new(1);
+        let threats = |rules| minimax.evaluate_threats(&game_with_rules(fen, rules), Player::White);
+
+        assert_eq!(threats(RulesConfig::default()), 0);
+        assert_ne!(threats(RulesConfig::pool()), 0);
+    }
+
+    #[test]
+    fn quiescence_counts_quiet_horizon_positions() {
+        let minimax = Minimax::new(1);
+        minimax.best_move(&Game::new()).unwrap();
+        let stats = minimax.quiesce_stats();
+        assert!(stats.qnodes_visited > 0);
+        assert_eq!(stats.horizon_hits, stats.qnodes_visited);
+
+        // every move of the white man can be captured right away
+        let minimax = Minimax::new(1);
+        minimax
+            .best_move(&Game::from_fen("8/8/8/8/3b4/8/3w4/8 w").unwrap())
+            .unwrap();
+        let stats = minimax.quiesce_stats();
+        assert!(stats.horizon_hits < stats.qnodes_visited);
+    }
+
+    #[test]
+    fn zugzwang_root_disables_null_move_pruning() {
+        // white king has to step next to the black king and gets captured
+        let cornered = Game::from_fen("10/10/10/10/10/10/10/2B7/10/W9 w").unwrap();
+        assert!(cornered.zugzwang_check(ZUGZWANG_CHECK_DEPTH));
+
+        let minimax = Minimax::new(4);
+        assert!(minimax.best_move(&cornered).is_some());
+        assert!(!minimax.null_move_pruning.get());
+
+        let quiet = Game::from_fen("6/b1b3/6/6/1w1w2/6 w").unwrap();
+        assert!(!quiet.zugzwang_check(ZUGZWANG_CHECK_DEPTH));
+        assert!(minimax.best_move(&quiet).is_some());
+        assert!(minimax.null_move_pruning.get());
+    }
+
+    #[test]
+    fn likely_zugzwang_skips_null_move() {
+        let minimax = Minimax::new(4);
+        minimax.null_move_pruning.set(true);
+
+        // cornered white king is less mobile than the black king
+        let kings_only = Game::from_fen("10/10/10/10/10/4B5/10/10/10/W9 w").unwrap();
+        assert!(kings_only.board.is_likely_zugzwang(Player::White));
+        assert!(!minimax.can_try_null_move(&kings_only, Player::White, 4, 1, 0));
+
+        let quiet = Game::from_fen("6/b1b3/6/6/1w1w2/6 w").unwrap();
+        assert!(minimax.can_try_null_move(&quiet, Player::White, 4, 1, 0));
+        assert!(!minimax.can_try_null_move(&quiet, Player::White, 4, 0, 0));
+        assert!(!minimax.can_try_null_move(&quiet, Player::White, NULL_MOVE_REDUCTION, 1, 0));
+    }
+
+    #[test]
+    fn moves_avoiding_recapture_keeps_captures_and_safe_moves() {
+        let game = Game::from_fen("8/8/8/4b3/8/2b1b3/1w6/6w1 w").unwrap();
+        let moves = game.moves_avoiding_recapture();
+        let has_move = |source: Index, target: Index| {
+            moves.iter().any(|available_move| {
+                game.board.to_white_index(available_move.source()) == source
+                    && game.board.to_white_index(available_move.target()) == target
+            })
+        };
+
+        // G8 F7 hangs the man to E6, H7 is safe
+        assert!(!has_move(
+            Index::new(6, 7, Player::White),
+            Index::new(5, 6, Player::White)
+        ));
+        assert!(has_move(
+            Index::new(6, 7, Player::White),
+            Index::new(7, 6, Player::White)
+        ));
+
+        let captures: Vec<AvailableMove> = game
+            .legal_moves()
+            .into_iter()
+            .filter(|available_move| available_move.is_capture())
+            .collect();
+        assert!(captures
+            .iter()
+            .any(|capture| matches!(capture.move_type(), MoveType::MultiCapture(_))));
+        for capture in &captures {
+            assert!(moves.contains(capture));
+        }
+    }
+
+    #[test]
+    fn move_reversal_mirrors_the_whole_path() {
+        let board = Board::new(8, 8);
+        let mut multi_capture = Move::new(
+            Index::new(1, 6, Player::Black),
+            Index::new(7, 0, Player::Black),
+        );
+        multi_capture.path = vec![
+            Index::new(3, 4, Player::Black),
+            Index::new(5, 2, Player::Black),
+        ];
+
+        let reversed = multi_capture.reversed(&board);
+
+        assert_eq!(reversed.source, Index::new(6, 1, Player::White));
+        assert_eq!(
+            reversed.path,
+            vec![
+                Index::new(4, 3, Player::White),
+                Index::new(2, 5, Player::White),
+            ]
+        );
+        assert_eq!(reversed.target, Index::new(0, 7, Player::White));
+    }
+
+    #[test]
+    fn black_multi_capture_follows_the_given_path() {
+        let mut game = Game::from_fen("3b4/2w1w3/8/2w1w3/8/8/8/8 b").unwrap();
+        game.rules = RulesConfig::pool();
+
+        game.make_move("D1 F3 D5").unwrap();
+
+        assert_eq!(game.board.get(3, 4), Ok(Tile::Black));
+        assert_eq!(game.board.get(2, 1), Ok(Tile::White));
+        assert_eq!(game.board.get(2, 3), Ok(Tile::White));
+        assert_eq!(game.board.get(4, 1), Ok(Tile::Empty));
+        assert_eq!(game.board.get(4, 3), Ok(Tile::Empty));
+    }
+
+    fn perft_by_successors(game: &Game, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        game.successors()
+            .iter()
+            .map(|(_, child)| perft_by_successors(child, depth - 1))
+            .sum()
+    }
+
+    #[test]
+    fn perft_counts_starting_position() {
+        let game = Game::new();
+        let counts: Vec<u64> = game
+            .perft_breakdown(4)
+            .into_iter()
+            .map(|(_, nodes)| nodes)
+            .collect();
+
+        assert_eq!(counts, vec![9, 81, 793, 7698]);
+        assert_eq!(game.perft(4), perft_by_successors(&game, 4));
+    }
+
+    #[test]
+    fn perft_batch_matches_applied_moves() {
+        let rules = RulesConfig {
+            flying_kings: true,
+            ..RulesConfig::default()
+        };
+        let game = game_with_rules("8/8/3b4/8/3b1b2/6w1/1b6/W7", rules);
+        assert!(game
+            .legal_moves()
+            .iter()
+            .any(|available_move| available_move.capture_count() > 1));
+
+        for depth in 1..=4 {
+            assert_eq!(game.perft(depth), perft_by_successors(&game, depth));
+        }
+    }
+
+    #[test]
+    fn clock_charges_moves_and_flags_on_timeout() {
+        let time_control = TimeControl::new(Duration::from_secs(60), Duration::from_secs(2));
+        let mut session = Checkers::new(CheckersConfig {
+            time_control: Some(time_control),
+            ..CheckersConfig::default()
+        });
+
+        session.record_move_time(Player::White, Duration::from_secs(10));
+        let clock = session.clock().unwrap();
+        assert_eq!(clock.remaining(Player::White), Duration::from_secs(52));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(60));
+        assert_eq!(clock.to_string(), "White 0:52 | Black 1:00");
+
+        session.record_move_time(Player::Black, Duration::from_secs(61));
+        assert!(session.clock().unwrap().is_flagged(Player::Black));
+        assert_eq!(session.current_game().state, GameState::Won(Player::White));
+
+        session.start_new_game();
+        assert_eq!(session.clock(), Some(&Clock::new(time_control)));
+    }
+
+    #[test]
+    fn new_game_keeps_session_config_and_records_the_last_game() {
+        let mut session = Checkers::new(CheckersConfig {
+            white_player: String::from("Alice"),
+            ..CheckersConfig::default()
+        });
+
+        session.start_new_game();
+        assert!(session.game_history().is_empty());
+
+        session.current_game_mut().make_move("D7 E6").unwrap();
+        session.start_new_game();
+        assert_eq!(session.game_history().len(), 1);
+        assert_eq!(session.config().white_player, "Alice");
+        assert_eq!(session.current_game().white_player_name(), Some("Alice"));
+        assert_eq!(session.current_game().half_move_number(), 0);
+    }
+
+    #[test]
+    fn untimed_session_has_no_clock() {
+        let mut session = Checkers::new(CheckersConfig::default());
+
+        session.record_move_time(Player::White, Duration::from_secs(3600));
+        assert_eq!(session.clock(), None);
+        assert_eq!(session.current_game().state, GameState::Turn(Player::White));
+    }
+
+    #[test]
+    fn bytes_round_trip_keeps_pieces_on_dark_squares() {
+        let board = Board::from_fen("1b1B2/6/6/6/1w1W2/w5").unwrap();
+        let bytes = board.serialize_to_bytes().unwrap();
+
+        assert_eq!(Board::deserialize_from_bytes(&bytes), Ok(board));
+        assert!(Board::deserialize_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn square_color_alternates_from_light_corner() {
+        assert_eq!(
+            Index::new(0, 0, Player::White).square_color(),
+            SquareColor::Light
+        );
+        assert_eq!(
+            Index::new(1, 0, Player::White).square_color(),
+            SquareColor::Dark
+        );
+        assert!(Index::new(0, 1, Player::Black).square_color().is_dark());
+        assert!(Index::new(3, 5, Player::White).square_color().is_light());
+    }
+
+    #[test]
+    fn legal_moves_for_the_waiting_player() {
+        let game = Game::new();
+        assert_eq!(game.state, GameState::Turn(Player::White));
+
+        let black_moves = game.legal_moves_for(Player::Black);
+        assert_eq!(black_moves.len(), 9);
+        for available_move in &black_moves {
+            assert_eq!(
+                game.board.get_tile_owner_at(available_move.source()),
+                Ok(Some(Player::Black))
+            );
+        }
+        assert_eq!(game.legal_moves_for(Player::White), game.legal_moves());
+    }
+
+    #[test]
+    fn rendered_tiles_take_configured_width() {
+        let board = Board::from_fen("1b1B2/6/6/6/1w1W2/w5").unwrap();
+        let ansi = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+
+        for tile_width in 1..=2 {
+            for glyph in &[BOARD_EMPTY, BOARD_MAN, BOARD_KING] {
+                assert_eq!(
+                    board.pad_glyph(*glyph, tile_width).chars().count(),
+                    tile_width
+                );
+            }
+
+            let drawn = board.get_drawed_board_with(&RenderOptions::new(tile_width));
+            let drawn = ansi.replace_all(&drawn, "");
+            // row label, border, tiles, border and padding
+            let line_width = 1 + 1 + 6 * tile_width + 1 + 1;
+            for line in drawn.lines() {
+                assert_eq!(line.chars().count(), line_width, "{:?}", line);
+            }
+        }
+    }
+
+    #[test]
+    fn last_move_is_set_after_a_move() {
+        let mut game = Game::new();
+        assert_eq!(game.last_move(), None);
+
+        game.make_move("D7 E6").unwrap();
+        let last_move = game.last_move().unwrap();
+        assert_eq!(
+            game.board.to_white_index(last_move.source()),
+            game.board.parse_notation("D7").unwrap()
+        );
+        assert_eq!(
+            game.board.to_white_index(last_move.target()),
+            game.board.parse_notation("E6").unwrap()
+        );
+
+        game.undo_move();
+        assert_eq!(game.last_move(), None);
+    }
+
+    #[test]
+    fn place_uses_white_coordinates() {
+        let mut board = Board::from_fen("6/6/6/6/6/6").unwrap();
+
+        board.place(1, 4, Tile::WhiteKing).unwrap();
+        assert_eq!(board.get(1, 4), Ok(Tile::WhiteKing));
+        assert_eq!(
+            board.get_tile(Index::new(4, 1, Player::Black)),
+            Ok(Tile::WhiteKing)
+        );
+
+        board
+            .set_tile(Index::new(0, 1, Player::Black), Tile::Black)
+            .unwrap();
+        assert_eq!(board.get(5, 4), Ok(Tile::Black));
+
+        assert!(board.place(6, 0, Tile::White).is_err());
+    }
+
+    #[test]
+    fn spread_kings_score_higher_than_clustered() {
+        let weights = EvalWeights::default();
+        let with_kings = |kings: &[(usize, usize)]| {
+            let mut board = Board::new_empty(10, 10);
+            for (x, y) in kings {
+                board.place(*x, *y, Tile::WhiteKing).unwrap();
+            }
+            board.king_distribution_score(Player::White, &weights)
+        };
+
+        let clustered = with_kings(&[(0, 9), (1, 8)]);
+        let spread = with_kings(&[(0, 9), (9, 0)]);
+
+        assert_eq!(clustered, 1);
+        assert_eq!(spread, 81);
+        assert_eq!(with_kings(&[(0, 9)]), 0);
+    }
+
+    #[test]
+    fn pieces_without_diagonal_neighbours_are_isolated() {
+        let mut board = Board::new_empty(10, 10);
+        for (x, y) in &[(6, 1), (7, 2), (4, 5), (0, 9)] {
+            board.place(*x, *y, Tile::White).unwrap();
+        }
+        board.place(5, 4, Tile::Black).unwrap();
+
+        assert_eq!(
+            board.get_isolated_pieces(Player::White),
+            vec![
+                Index::new(4, 5, Player::White),
+                Index::new(0, 9, Player::White)
+            ]
+        );
+        assert!(EvalWeights::default().isolation_penalty < 0);
+    }
+
+    #[test]
+    fn moving_from_an_empty_square_is_empty_source() {
+        let mut game = Game::new();
+
+        assert_eq!(game.make_move("E6 F5"), Err(CheckersError::EmptySource));
+        assert_eq!(
+            game.check_move(Move::new(
+                Index::new(4, 5, Player::White),
+                Index::new(5, 4, Player::White)
+            )),
+            Err(CheckersError::EmptySource)
+        );
+        assert_eq!(game.half_move_number(), 0);
+    }
+
+    #[test]
+    fn bridge_count_counts_same_row_pairs() {
+        let mut board = Board::new_empty(10, 10);
+        board.place(0, 3, Tile::White).unwrap();
+        board.place(2, 3, Tile::White).unwrap();
+        assert_eq!(board.bridge_count(Player::White), 1);
+
+        board.place(4, 3, Tile::WhiteKing).unwrap();
+        board.place(8, 3, Tile::White).unwrap();
+        assert_eq!(board.bridge_count(Player::White), 2);
+        assert_eq!(board.bridge_count(Player::Black), 0);
+    }
+
+    #[test]
+    fn opening_branching_factor_on_10x10() {
+        let mut game = Game::new();
+        assert_eq!(game.branching_factor(), 9);
+        assert_eq!(game.average_branching_factor(), None);
+
+        game.make_move("D7 E6").unwrap();
+        assert_eq!(game.branching_factor(), 9);
+        assert_eq!(game.average_branching_factor(), Some(9.0));
+    }
+
+    #[test]
+    fn man_move_attacking_two_men_is_a_fork() {
+        let game = Game::from_fen("8/8/8/8/3b1b2/8/3w4/8 w").unwrap();
+        let fork_targets: Vec<Index> = game
+            .get_fork_moves()
+            .iter()
+            .map(|fork| game.board.to_white_index(fork.target()))
+            .collect();
+
+        assert_eq!(fork_targets, vec![Index::new(4, 5, Player::White)]);
+
+        let ordered = Minimax::order_moves(&game, game.legal_moves());
+        assert_eq!(ordered[0], game.get_fork_moves()[0]);
+    }
+
+    #[test]
+    fn king_behind_two_enemy_pawns_is_a_skewer() {
+        let game = game_with_rules("8/8/8/4b3/8/2b5/8/W7", RulesConfig::pool());
+
+        assert_eq!(
+            game.get_skewer_threats(Player::White),
+            vec![(
+                Index::new(0, 7, Player::White),
+                Index::new(2, 5, Player::White),
+                Index::new(4, 3, Player::White)
+            )]
+        );
+        assert!(game.get_skewer_threats(Player::Black).is_empty());
+
+        // only kings skewer
+        let game = game_with_rules("8/8/8/4b3/8/2b5/8/w7", RulesConfig::pool());
+        assert!(game.get_skewer_threats(Player::White).is_empty());
+    }
+
+    #[test]
+    fn open_diagonal_length_stops_at_pawns_and_edges() {
+        let king = Index::new(0, 7, Player::White);
+
+        let open = Board::from_fen("8/8/8/8/8/8/8/W7").unwrap();
+        assert_eq!(open.open_diagonal_length(king, Direction::NE), 7);
+        assert_eq!(open.open_diagonal_length(king, Direction::SW), 0);
+        // directions are relative to the index orientation
+        assert_eq!(
+            open.open_diagonal_length(Index::new(7, 0, Player::Black), Direction::SW),
+            7
+        );
+
+        let blocked = Board::from_fen("8/8/8/8/8/8/1w6/W7").unwrap();
+        assert_eq!(blocked.open_diagonal_length(king, Direction::NE), 0);
+    }
+
+    #[test]
+    fn diagonal_length_counts_tiles_to_the_edge() {
+        let board = Board::new(10, 10);
+        let corner = Index::new(0, 9, Player::White);
+
+        assert_eq!(board.diagonal_length(corner, Direction::NE), 10);
+        assert_eq!(board.diagonal_length(corner, Direction::SW), 1);
+        assert_eq!(board.max_diagonal_length(), 10);
+        assert_eq!(Board::new(8, 8).max_diagonal_length(), 8);
+    }
+
+    #[test]
+    fn king_squares_capturable_by_a_man_are_unsafe() {
+        let king = Index::new(1, 6, Player::White);
+
+        // man on E4 can capture on D5 and F5, but not next to the king
+        let board = Board::from_fen("8/8/8/4b3/8/8/1W6/8").unwrap();
+        let threatened = board.get_threatened_squares(Player::Black);
+        assert!(threatened.contains(&Index::new(3, 4, Player::White)));
+        assert!(threatened.contains(&Index::new(5, 4, Player::White)));
+        assert!(!threatened.contains(&Index::new(2, 5, Player::White)));
+
+        let safe = board.safe_king_squares(Player::White);
+        assert!(!safe.contains(&Index::new(3, 4, Player::White)));
+        assert!(safe.contains(&Index::new(2, 5, Player::White)));
+        assert!(!safe.contains(&king));
+        assert_eq!(board.count_safe_king_moves(king, Player::White), 4);
+
+        // man on B5 can capture on C6
+        let board = Board::from_fen("8/8/8/8/1b6/8/1W6/8").unwrap();
+        assert_eq!(board.count_safe_king_moves(king, Player::White), 3);
+    }
+
+    #[test]
+    fn legal_moves_are_sorted_by_source_then_target() {
+        let game = Game::new();
+        let moves = game.legal_moves();
+
+        assert_eq!(moves, game.legal_moves());
+        assert!(moves.windows(2).all(
+            |pair| (pair[0].source(), pair[0].target()) < (pair[1].source(), pair[1].target())
+        ));
+        assert!(Index::new(9, 0, Player::White) < Index::new(0, 1, Player::White));
+    }
+
+    #[test]
+    fn capturing_the_last_pawn_is_a_winning_move() {
+        let game = Game::from_fen("8/8/8/8/8/4b3/3w4/8 w").unwrap();
+        let winning_move = game.find_winning_move().unwrap();
+
+        assert!(winning_move.is_capture());
+        assert_eq!(
+            game.board.to_white_index(winning_move.target()),
+            Index::new(5, 4, Player::White)
+        );
+
+        let game = Game::from_fen("1b6/8/8/8/8/4b3/3w4/8 w").unwrap();
+        assert_eq!(game.find_winning_move(), None);
+    }
+
+    #[test]
+    fn supported_pawn_is_defended() {
+        let game = Game::from_fen("8/8/5w2/8/3w4/8/7w/8 w").unwrap();
+
+        assert!(game.is_defended(Index::new(3, 4, Player::White), Player::White));
+        assert!(game.is_defended(Index::new(5, 2, Player::White), Player::White));
+        assert!(!game.is_defended(Index::new(7, 6, Player::White), Player::White));
+        assert!(!game.is_defended(Index::new(3, 4, Player::White), Player::Black));
+    }
+
+    #[test]
+    fn starting_board_bytes_round_trip() {
+        let mut board = Board::new(10, 10);
+        board.place(5, 4, Tile::WhiteKing).unwrap();
+        board.place(4, 5, Tile::BlackKing).unwrap();
+        let bytes = board.serialize_to_bytes().unwrap();
+
+        // 2 header bytes and 3 bits for each of 50 dark tiles
+        assert_eq!(bytes.len(), 2 + 19);
+        assert_eq!(Board::deserialize_from_bytes(&bytes), Ok(board));
+
+        assert_eq!(
+            Board::new_empty(256, 8).serialize_to_bytes(),
+            Err(CheckersError::BoardTooBigToSerialize {
+                height: 256,
+                width: 8
+            })
+        );
+    }
+
+    #[test]
+    fn write_board_streams_the_drawn_board() {
+        let board = Board::new(10, 10);
+        let mut buffer = Vec::new();
+
+        board.write_board(&mut buffer).unwrap();
+
+        assert!(!buffer.is_empty());
+        assert_eq!(buffer, board.get_drawed_board().into_bytes());
+    }
+
+    #[test]
+    fn break_rule_prefers_capturing_kings() {
+        let fen = "8/8/8/8/8/2b1B3/3w4/8";
+        let captured = |rules| {
+            let game = game_with_rules(fen, rules);
+            game.legal_moves()
+                .iter()
+                .flat_map(|capture| game.board.get_captured_indices(capture))
+                .map(|index| game.board.to_white_index(index))
+                .collect::<Vec<Index>>()
+        };
+        let max_capture = RulesConfig {
+            max_capture: true,
+            ..RulesConfig::default()
+        };
+
+        assert_eq!(captured(max_capture).len(), 2);
+        assert_eq!(
+            captured(RulesConfig {
+                max_kings_capture: true,
+                ..max_capture
+            }),
+            vec![Index::new(4, 5, Player::White)]
+        );
+    }
+
+    #[test]
+    fn two_king_position_is_endgame() {
+        let game = Game::from_fen("10/10/10/10/10/4B5/10/10/10/W9 w").unwrap();
+        assert_eq!(game.phase(), GamePhase::Endgame);
+
+        assert_eq!(Game::new().phase(), GamePhase::Opening);
+    }
+
+    #[test]
+    fn boards_are_quiet_by_default() {
+        let board = Board::new(10, 10);
+        let boards = [
+            Board::new_empty(10, 10),
+            Board::from_fen("6/6/6/6/3b2/W5").unwrap(),
+            Board::deserialize_from_bytes(&board.serialize_to_bytes().unwrap()).unwrap(),
+            board.clone(),
+        ];
+
+        for board in boards.iter() {
+            assert!(!board.debug);
+        }
+        assert!(!Game::new().board.debug);
+    }
+
+    #[test]
+    fn from_move_list_replays_moves() {
+        let game =
+            Game::from_move_list(&["D7 E6", "E4 D5", "B7 A6"], RulesConfig::default()).unwrap();
+
+        assert_eq!(game.half_move_number(), 3);
+        assert_eq!(game.state, GameState::Turn(Player::Black));
+        for (square, tile) in &[
+            ("E6", Tile::White),
+            ("A6", Tile::White),
+            ("D5", Tile::Black),
+            ("D7", Tile::Empty),
+            ("E4", Tile::Empty),
+            ("B7", Tile::Empty),
+        ] {
+            let index = game.board.parse_notation(square).unwrap();
+            assert_eq!(game.board.get_tile(index), Ok(*tile), "{}", square);
+        }
+
+        match Game::from_move_list(&["D7 E6", "E6 F5"], RulesConfig::default()) {
+            Err(CheckersError::InvalidMove { move_number, .. }) => assert_eq!(move_number, 2),
+            other => panic!("Expected invalid second move, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn game_record_replays_into_the_same_game() {
+        let mut game = Game::from_move_list(&["D7 E6", "E4 D5"], RulesConfig::default()).unwrap();
+        game.set_player_names(String::from("Alice"), String::from("Bob"));
+
+        let replayed = Game::from_game_record(game.to_game_record()).unwrap();
+
+        assert_eq!(replayed.board, game.board);
+        assert_eq!(replayed.state, game.state);
+        assert_eq!(replayed.white_player_name(), Some("Alice"));
+        assert_eq!(replayed.black_player_name(), Some("Bob"));
+    }
+
+    #[test]
+    fn mirror_positions_share_canonical_board() {
+        let board = Board::from_fen("8/2b5/8/8/8/8/3w4/8").unwrap();
+        let rotated = Board::from_fen("8/4b3/8/8/8/8/5w2/8").unwrap();
+        let other = Board::from_fen("8/2b5/8/8/8/8/5w2/8").unwrap();
+
+        assert_ne!(board, rotated);
+        assert_eq!(board.canonical().0, rotated.canonical().0);
+        assert_ne!(board.canonical().1, rotated.canonical().1);
+        assert_ne!(board.canonical().0, other.canonical().0);
+    }
+
+    #[test]
+    fn canonical_positions_keep_the_player_to_move() {
+        let position = |fen| Game::from_fen(fen).unwrap().canonical_position();
+        let white_to_move = position("8/2b5/8/8/8/8/3w4/8 w");
+        let rotated_black_to_move = position("8/4b3/8/8/8/8/5w2/8 b");
+        let rotated_white_to_move = position("8/4b3/8/8/8/8/5w2/8 w");
+
+        assert_eq!(white_to_move, rotated_black_to_move);
+        assert_ne!(white_to_move, rotated_white_to_move);
+    }
+
+    #[test]
+    fn capture_count_of_every_move_type() {
+        let index = |x, y| Index::new(x, y, Player::White);
+        let count = |move_type| AvailableMove::new(index(3, 6), move_type).capture_count();
+
+        assert_eq!(count(MoveType::Move(index(2, 5))), 0);
+        assert_eq!(count(MoveType::KingMove(index(0, 3))), 0);
+        assert_eq!(
+            count(MoveType::Capture {
+                target_index: index(5, 4),
+                captured_index: index(4, 5)
+            }),
+            1
+        );
+        assert_eq!(
+            count(MoveType::KingCapture {
+                target_index: index(6, 3),
+                captured_index: index(5, 4)
+            }),
+            1
+        );
+        assert_eq!(
+            count(MoveType::MultiCapture(vec![index(5, 4), index(3, 2)])),
+            2
+        );
+        assert_eq!(
+            count(MoveType::KingMultiCapture(vec![
+                index(5, 4),
+                index(3, 2),
+                index(1, 4)
+            ])),
+            3
+        );
+    }
+
+    #[test]
+    fn html_table_has_a_cell_for_every_tile() {
+        let board = Board::new(10, 8);
+        let options = HtmlRenderOptions {
+            highlight_squares: vec![(Index::new(1, 0, Player::White), String::from("last"))],
+            ..HtmlRenderOptions::default()
+        };
+        let html = board.to_html_table(&options);
+
+        assert_eq!(html.matches("<td").count(), 10 * 8);
+        assert_eq!(html.matches("<th>").count(), 8 + 10 + 1);
+        assert_eq!(html.matches("checkers-black").count(), 16);
+        assert_eq!(html.matches(" last\"").count(), 1);
+    }
+
+    #[test]
+    fn square_numbers_map_to_dark_tiles() {
+        let board = Board::new(10, 10);
+        let square = |n| Index::from_square_number(n, &board, Player::White);
+
+        assert_eq!(square(1), Ok(Index::new(1, 0, Player::White)));
+        assert_eq!(square(6), Ok(Index::new(0, 1, Player::White)));
+        assert_eq!(square(50), Ok(Index::new(8, 9, Player::White)));
+        assert_eq!(square(0), Err(CheckersError::OutsideBoard));
+        assert_eq!(square(51), Err(CheckersError::OutsideBoard));
+        assert_eq!(
+            Index::from_square_number(1, &board, Player::Black),
+            Ok(Index::new(8, 9, Player::Black))
+        );
+
+        for n in 1..=50 {
+            assert_eq!(square(n).unwrap().to_square_number(&board), Ok(n));
+        }
+        assert!(Index::new(0, 0, Player::White)
+            .to_square_number(&board)
+            .is_err());
+    }
+
+    #[test]
+    fn has_king_flips_after_promotion() {
+        let mut game = Game::from_fen("6/2w3/6/6/3b2/6 w").unwrap();
+        assert!(!game.board.has_king(Player::White));
+
+        game.make_move("C2 B1").unwrap();
+        assert!(game.board.has_king(Player::White));
+        assert!(!game.board.has_king(Player::Black));
+    }
+
+    #[test]
+    fn read_input_returns_none_at_end_of_input() {
+        let mut input = io::Cursor::new("D7 E6\n");
+
+        assert_eq!(read_input(&mut input), Some(String::from("D7 E6\n")));
+        assert_eq!(read_input(&mut input), None);
+        assert_eq!(read_input(&mut io::Cursor::new("")), None);
+    }
+
+    #[test]
+    fn legal_moves_grouped_by_source() {
+        let game = Game::new();
+        let moves = game.legal_moves();
+        let moves_by_source = game.legal_moves_by_source();
+
+        let mut sources: Vec<Index> = moves_by_source.keys().copied().collect();
+        sources.sort();
+        let mut movable: Vec<Index> = moves.iter().map(|m| m.source()).collect();
+        movable.dedup();
+
+        assert_eq!(sources, movable);
+        assert_eq!(sources.len(), 5);
+        assert_eq!(
+            moves_by_source.values().map(Vec::len).sum::<usize>(),
+            moves.len()
+        );
+    }
+
+    #[test]
+    fn starting_position_has_equal_tempo() {
+        let mut game = Game::new();
+        assert_eq!(
+            game.board.compute_tempo(Player::White),
+            game.board.compute_tempo(Player::Black)
+        );
+        assert_eq!(game.tempo_difference(), 0);
+
+        game.make_move("D7 E6").unwrap();
+        assert_eq!(game.tempo_difference(), 1);
+    }
+
+    #[test]
+    fn captures_sidebar_lists_captured_glyphs() {
+        let board = Board::new_empty(10, 10);
+        let drawn = board.get_drawed_board_with_captures(
+            &[Tile::Black, Tile::Black, Tile::BlackKing],
+            &[Tile::White],
+        );
+        let plain = board.get_drawed_board();
+        let sidebar: Vec<&str> = drawn
+            .lines()
+            .zip(plain.lines())
+            .map(|(line, board_line)| line.strip_prefix(board_line).unwrap().trim_start())
+            .collect();
+        let count = |text: &str, glyph| text.matches(glyph).count();
+
+        assert_eq!(sidebar[1], "Captured by white:");
+        assert_eq!(count(sidebar[2], BOARD_MAN), 2);
+        assert_eq!(count(sidebar[2], BOARD_KING), 1);
+        assert_eq!(sidebar[4], "Captured by black:");
+        assert_eq!(count(sidebar[5], BOARD_MAN), 1);
+        assert_eq!(count(sidebar[5], BOARD_KING), 0);
+        assert_eq!(drawn.lines().count(), plain.lines().count());
+    }
+
+    #[test]
+    fn cloned_multi_capture_is_independent() {
+        let mut original = AvailableMove::new(
+            Index::new(3, 6, Player::White),
+            MoveType::MultiCapture(vec![
+                Index::new(5, 4, Player::White),
+                Index::new(3, 2, Player::White),
+            ]),
+        );
+        let copy = original.clone();
+
+        if let MoveType::MultiCapture(path) = &mut original.move_type {
+            path.push(Index::new(1, 0, Player::White));
+        }
+
+        assert_eq!(copy.capture_count(), 2);
+        assert_eq!(original.capture_count(), 3);
+        assert_ne!(copy, original);
+
+        let mut game = Game::new();
+        game.make_move("D7 E6").unwrap();
+        let snapshot = game.clone();
+        game.make_move("E4 D5").unwrap();
+        assert_eq!(snapshot.history.len(), 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "on light tile")]
+    fn invariants_catch_pawns_on_light_tiles() {
+        let mut board = Board::new_empty(8, 8);
+        board
+            .set_tile(Index::new(0, 0, Player::White), Tile::White)
+            .unwrap();
+
+        board.check_invariants();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Unpromoted")]
+    fn invariants_catch_unpromoted_men() {
+        let mut board = Board::new_empty(8, 8);
+        board.place(1, 0, Tile::White).unwrap();
+
+        board.check_invariants();
+    }
+
+    #[test]
+    fn square_query_command_lists_destinations() {
+        let mut game = Game::new();
+
+        assert_eq!(
+            describe_square_moves(&game, Some("D7")),
+            "D7 can move to: C6, E6"
+        );
+        assert_eq!(
+            describe_square_moves(&game, Some("C8")),
+            "Pawn on C8 can't move"
+        );
+        assert_eq!(describe_square_moves(&game, Some("E6")), "E6 is empty");
+        assert_eq!(
+            describe_square_moves(&game, Some("C4")),
+            "C4 is a pawn of the other player"
+        );
+        assert_eq!(
+            describe_square_moves(&game, None),
+            "Usage: ? <square>, ex. ? C3"
+        );
+
+        assert!(handle_command(&mut game, "? D7"));
+        assert!(!handle_command(&mut game, "D7 E6"));
+        assert_eq!(game.half_move_number(), 0);
+    }
+
+    #[test]
+    fn line_command_makes_every_move() {
+        let mut game = Game::new();
+
+        assert!(handle_command(&mut game, "LINE D7 E6 E4 D5"));
+        assert_eq!(game.half_move_number(), 2);
+        assert_eq!(game.state, GameState::Turn(Player::White));
+    }
+
+    #[test]
+    fn capturing_a_king_with_a_man_wins_material() {
+        let game = Game::from_fen("8/8/8/8/8/2B1b3/3w4/8 w").unwrap();
+        let captures = game.get_material_winning_captures();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(
+            game.board.to_white_index(captures[0].target()),
+            Index::new(1, 4, Player::White)
+        );
+
+        // without a winning capture even trades are returned
+        let game = Game::from_fen("8/8/8/8/8/2b1b3/3w4/8 w").unwrap();
+        assert_eq!(game.get_material_winning_captures().len(), 2);
+    }
+
+    #[test]
+    fn peek_leaves_the_game_unchanged() {
+        let game = Game::from_fen("8/8/8/8/8/4b3/3w4/8 w").unwrap();
+        let capture = Move::new(
+            Index::new(3, 6, Player::White),
+            Index::new(5, 4, Player::White),
+        );
+
+        assert_eq!(game.peek(&[capture]), Ok(GameState::Won(Player::White)));
+        assert_eq!(game.state, GameState::Turn(Player::White));
+        assert_eq!(game.to_fen(), "8/8/8/8/8/4b3/3w4/8 w");
+        assert_eq!(game.half_move_number(), 0);
+
+        // men don't move backwards
+        let illegal = Move::new(
+            Index::new(3, 6, Player::White),
+            Index::new(2, 7, Player::White),
+        );
+        assert_eq!(game.peek(&[illegal]), Err(CheckersError::IllegalMove));
+    }
+
+    #[test]
+    fn corners_of_the_standard_board() {
+        let board = Board::new(8, 8);
+        let corner = |x, y| board.corner_kind(Index::new(x, y, Player::White));
+
+        assert_eq!(corner(7, 0), Some(CornerKind::SingleCorner));
+        assert_eq!(corner(0, 7), Some(CornerKind::SingleCorner));
+        for (x, y) in &[(1, 0), (0, 1), (6, 7), (7, 6)] {
+            assert_eq!(corner(*x, *y), Some(CornerKind::DoubleCorner));
+        }
+        assert_eq!(corner(0, 0), None);
+        assert_eq!(corner(3, 4), None);
+        assert_eq!(
+            board.corner_kind(Index::new(7, 0, Player::Black)),
+            Some(CornerKind::SingleCorner)
         );
+    }
 
-        match self.state {
-            GameState::Turn(Player::White) => Ok(game_move),
-            GameState::Turn(Player::Black) => Ok(self.reverse_move(&game_move)),
-            _ => panic!("The game has ended already"),
+    #[test]
+    fn triple_capture_removes_every_captured_pawn() {
+        let mut game = Game::from_fen("1b6/8/5b2/8/3b4/8/1b6/w7 w").unwrap();
+
+        game.make_move("A8 G2").unwrap();
+
+        let last_move = game.last_move().unwrap();
+        assert_eq!(last_move.capture_count(), 3);
+        assert_eq!(game.board.get(6, 1), Ok(Tile::White));
+        for (x, y) in &[(0, 7), (1, 6), (3, 4), (5, 2)] {
+            assert_eq!(game.board.get(*x, *y), Ok(Tile::Empty));
+        }
+        assert_eq!(game.board.get(1, 0), Ok(Tile::Black));
+    }
+
+    #[test]
+    fn info_string_has_every_legend() {
+        let info = Board::new(10, 10).info_string();
+
+        for legend in &[
+            "Empty tile:",
+            "White man:",
+            "White king:",
+            "Black man:",
+            "Black king:",
+        ] {
+            assert!(info.contains(legend), "{} missing", legend);
         }
+        assert_eq!(info.lines().count(), 5);
+        assert_eq!(info.matches(BOARD_MAN).count(), 2);
+        assert_eq!(info.matches(BOARD_KING).count(), 2);
     }
 
-    pub fn check_move<'a>(&self, game_move: Move) -> Result<AvailableMove, &'a str> {
-        if let GameState::Won(_) = self.state {
-            return Err("The game is already finished");
+    #[test]
+    fn pool_rules_restrict_men_but_fly_kings() {
+        let fen = "8/8/5w2/6b1/3b4/8/8/W7";
+        let captures = |rules| {
+            let game = game_with_rules(fen, rules);
+            game.legal_captures_for(Player::White)
+                .iter()
+                .map(|capture| {
+                    (
+                        game.board.to_white_index(capture.source()),
+                        game.board.to_white_index(capture.target()),
+                    )
+                })
+                .collect::<Vec<(Index, Index)>>()
         };
+        let index = |x, y| Index::new(x, y, Player::White);
+
+        // man can't capture backwards, king captures from a distance
+        assert_eq!(
+            captures(RulesConfig::pool()),
+            vec![(index(0, 7), index(4, 3))]
+        );
+        assert_eq!(
+            captures(RulesConfig::default()),
+            vec![(index(5, 2), index(7, 4))]
+        );
+    }
 
-        println!("Move: {:?}", game_move);
+    #[test]
+    fn capture_chains_tree_shows_every_branch() {
+        let game = Game::from_fen("8/8/8/2b5/8/2b1b3/3w4/8 w").unwrap();
+        let tree = game
+            .visualize_capture_chains(Index::new(3, 6, Player::White))
+            .unwrap();
 
-        // check if move is valid
-        let available_moves = match self.get_allowed_moves_for(game_move.source) {
-            Ok(available_moves) => available_moves,
-            Err(msg) => return Err(msg),
+        assert_eq!(tree, "D7\n├── x B5\n│   └── x D3\n└── x F5\n");
+    }
+
+    #[test]
+    fn move_leaving_only_captures_is_forcing() {
+        let game = Game::from_fen("8/8/8/8/5b2/6w1/3w3w/8 w").unwrap();
+        let white_move = |x, y| {
+            Move::new(
+                Index::new(3, 6, Player::White),
+                Index::new(x, y, Player::White),
+            )
         };
 
-        println!("Available moves: {:?}", available_moves);
+        assert_eq!(game.is_forcing(white_move(4, 5)), Ok(true));
+        assert_eq!(game.is_forcing(white_move(2, 5)), Ok(false));
+        assert_eq!(
+            game.is_forcing(white_move(3, 4)),
+            Err(CheckersError::IllegalMove)
+        );
+    }
 
-        if let Some(game_move) = Game::find_move_in_available(available_moves, game_move) {
-            Ok(game_move)
-        } else {
-            Err("Illegal move")
-        }
+    #[test]
+    fn man_next_to_empty_king_row_is_a_promotion_threat() {
+        let board = Board::from_fen("8/2w5/8/8/8/8/5b2/8").unwrap();
+        assert!(board.has_promotion_threat(Player::White));
+        assert_eq!(board.count_promotion_threats(Player::White), 1);
+        assert_eq!(board.count_promotion_threats(Player::Black), 1);
+
+        let blocked = Board::from_fen("1W1W4/2w5/8/8/8/8/8/8").unwrap();
+        assert!(!blocked.has_promotion_threat(Player::White));
+
+        // enemy pieces on the king row don't block the threat
+        let enemy = Board::from_fen("1b1b4/2w5/8/8/8/8/8/8").unwrap();
+        assert_eq!(enemy.count_promotion_threats(Player::White), 1);
+
+        let king = Board::from_fen("8/2W5/8/8/8/8/8/8").unwrap();
+        assert!(!king.has_promotion_threat(Player::White));
+        assert!(EvalWeights::default().promotion_threat_penalty < 0);
     }
 
-    pub fn find_move_in_available(
-        available_moves: Vec<AvailableMove>,
-        game_move: Move,
-    ) -> Option<AvailableMove> {
-        // TODO: implement multi-captures
-        let available_targets: Vec<&AvailableMove> = available_moves
-            .iter()
-            // take only non-multicapture moves
-            .filter(|x| match x.move_type() {
-                MoveType::MultiCapture(_) => false,
-                MoveType::KingMultiCapture(_) => false,
-                _ => true,
-            })
-            .collect();
+    #[test]
+    fn longest_capture_finds_the_triple_capture() {
+        let game = Game::from_fen("1b6/8/5b2/8/3b4/8/1b6/w7 w").unwrap();
+        assert_eq!(
+            game.longest_capture(),
+            Some((Index::new(0, 7, Player::White), 3))
+        );
 
-        let available_move = available_targets.into_iter().find(|x| match x.move_type() {
-            MoveType::Move(index) => index == &game_move.target,
-            MoveType::Capture { target_index, .. } => target_index == &game_move.target,
-            MoveType::KingMove(index) => index == &game_move.target,
-            MoveType::KingCapture { target_index, .. } => target_index == &game_move.target,
-            MoveType::KingMultiCapture(_) => false,
-            MoveType::MultiCapture(_) => false,
-        });
+        assert_eq!(Game::new().longest_capture(), None);
+    }
+
+    #[test]
+    fn rows_bottom_up_flips_only_row_labels() {
+        let board = Board::new(10, 10);
+        let ansi = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+        let row_labels = |rows_bottom_up| {
+            let options = RenderOptions {
+                rows_bottom_up,
+                ..RenderOptions::default()
+            };
+            let drawn = board.get_drawed_board_with(&options);
+            ansi.replace_all(&drawn, "")
+                .lines()
+                .skip(2)
+                .take(10)
+                .map(|line| line[..2].trim().parse::<usize>().unwrap())
+                .collect::<Vec<usize>>()
+        };
+
+        assert_eq!(row_labels(false), (1..=10).collect::<Vec<usize>>());
+        assert_eq!(row_labels(true), (1..=10).rev().collect::<Vec<usize>>());
+
+        let strip_labels = |rows_bottom_up| {
+            let options = RenderOptions {
+                rows_bottom_up,
+                ..RenderOptions::default()
+            };
+            ansi.replace_all(&board.get_drawed_board_with(&options), "")
+                .lines()
+                .map(|line| line.replace(|c: char| c.is_ascii_digit() || c == ' ', ""))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(strip_labels(false), strip_labels(true));
+    }
+
+    #[test]
+    fn single_capturable_enemy_piece() {
+        let game = Game::from_fen("1b6/8/8/8/8/4b3/3w4/8 w").unwrap();
 
-        available_move.cloned()
+        assert_eq!(
+            game.capturable_enemy_pieces(),
+            vec![Index::new(4, 5, Player::White)]
+        );
+        assert!(Game::new().capturable_enemy_pieces().is_empty());
     }
 
-    pub fn get_allowed_moves_for<'a>(&self, source: Index) -> Result<Vec<AvailableMove>, &'a str> {
-        let pawn = match self.board.get_tile(source) {
-            Ok(tile) => tile,
-            Err(msg) => return Err(msg),
+    #[test]
+    fn promoted_man_continues_capturing_as_king() {
+        let fen = "8/2b1b3/1w6/8/8/8/8/8";
+        let longest = |rules| {
+            let game = game_with_rules(fen, rules);
+            game.legal_captures_for(Player::White)
+                .iter()
+                .map(AvailableMove::capture_count)
+                .max()
         };
 
-        // Check if source tile is empty
-        if pawn == Tile::Empty {
-            return Err("Source is an empty tile");
-        }
+        assert_eq!(longest(RulesConfig::pool()), Some(1));
+        assert_eq!(
+            longest(RulesConfig {
+                king_continues_capture: true,
+                ..RulesConfig::pool()
+            }),
+            Some(2)
+        );
+    }
 
-        let mut available_moves = Vec::new();
+    #[test]
+    fn only_kings_board_places_kings() {
+        let board = Board::new_only_kings(10, 10, &["E6"], &["D5"]).unwrap();
 
-        match pawn {
-            // Check for man moves
-            Tile::White | Tile::Black => {
-                println!("Checking diagonal moves");
+        assert_eq!(board.material_array(), [98, 0, 1, 0, 1]);
+        assert_eq!(board.get(4, 5), Ok(Tile::WhiteKing));
+        assert_eq!(board.get(3, 4), Ok(Tile::BlackKing));
 
-                // Check diagonal moves
-                if let Some(left_diagonal) = source.translate(-1, -1) {
-                    if self.board.get_tile(left_diagonal) == Ok(Tile::Empty) {
-                        available_moves
-                            .push(AvailableMove::new(source, MoveType::Move(left_diagonal)));
-                    }
-                }
+        // E5 is a light tile
+        assert!(Board::new_only_kings(10, 10, &["E5"], &["D5"]).is_err());
+        assert_eq!(
+            Board::new_only_kings(10, 10, &["K1"], &[]),
+            Err(CheckersError::OutsideBoard)
+        );
+    }
 
-                if let Some(right_diagonal) = source.translate(1, -1) {
-                    if self.board.get_tile(right_diagonal) == Ok(Tile::Empty) {
-                        available_moves
-                            .push(AvailableMove::new(source, MoveType::Move(right_diagonal)));
-                    }
-                }
+    #[test]
+    fn piece_square_table_favors_advanced_pawns() {
+        // rows closer to the enemy side are worth more, same table for both players
+        let table: Vec<i32> = (0..36).map(|i| 6 - i / 6).collect();
+        let board = Board::from_fen("6/2w3/6/6/1w1b2/6").unwrap();
 
-                // Check for captures
-                if let Ok(available_move) = self.check_capture_move(source, Direction::NE) {
-                    available_moves.push(available_move);
-                }
-                if let Ok(available_move) = self.check_capture_move(source, Direction::NW) {
-                    available_moves.push(available_move);
-                }
-                if let Ok(available_move) = self.check_capture_move(source, Direction::SE) {
-                    available_moves.push(available_move);
-                }
-                if let Ok(available_move) = self.check_capture_move(source, Direction::SW) {
-                    available_moves.push(available_move);
-                }
+        assert_eq!(board.piece_square_score(Player::White, &table), 5 + 2);
+        assert_eq!(board.piece_square_score(Player::Black, &table), 5);
+    }
 
-                // Check for multi-captures
-                // TODO: implement multi-captures, probably recursively
-            }
-            // Check for king moves
-            Tile::WhiteKing | Tile::BlackKing => {
-                // TODO: implement king move
+    #[test]
+    #[should_panic(expected = "Table needs a value for every tile")]
+    fn piece_square_table_needs_every_tile() {
+        Board::new(6, 6).piece_square_score(Player::White, &[1; 35]);
+    }
 
-                // TODO: implement king captures
+    #[test]
+    fn is_legal_move_checks_source_and_target() {
+        let game = Game::new();
+
+        assert_eq!(game.is_legal_move("D7", "E6"), Ok(true));
+        assert_eq!(game.is_legal_move("d7", "c6"), Ok(true));
+        assert_eq!(game.is_legal_move("D7", "E5"), Ok(false));
+        // empty source
+        assert_eq!(game.is_legal_move("E6", "F5"), Ok(false));
+        // black pawns are checked from their own perspective
+        assert_eq!(game.is_legal_move("C4", "D5"), Ok(true));
+        assert!(game.is_legal_move("D7", "Z1").is_err());
+    }
 
-                // TODO: implement king multi captures
-            }
-            Tile::Empty => panic!(INTERNAL_ERROR_MESSAGE),
-        }
+    #[test]
+    fn capture_marks_source_captured_and_target_dirty() {
+        let mut game = Game::from_fen("1b6/8/8/8/8/4b3/3w4/8 w").unwrap();
+        assert!(game.dirty_squares().is_empty());
 
-        Ok(available_moves)
+        game.make_move("D7 F5").unwrap();
+        assert_eq!(
+            game.dirty_squares(),
+            &[
+                Index::new(3, 6, Player::White),
+                Index::new(4, 5, Player::White),
+                Index::new(5, 4, Player::White)
+            ]
+        );
+
+        game.make_move("B1 A2").unwrap();
+        assert_eq!(
+            game.dirty_squares(),
+            &[
+                Index::new(1, 0, Player::White),
+                Index::new(0, 1, Player::White)
+            ]
+        );
     }
 
-    fn check_capture_move(
-        &self,
-        source: Index,
-        direction: Direction,
-    ) -> Result<AvailableMove, &str> {
-        // Check if source is a pawn on the board
-        let source_tile = match self.board.get_tile(source) {
-            Ok(source_tile) => source_tile,
-            Err(msg) => return Err(msg),
-        };
+    #[test]
+    fn diamond_of_four_pawns_has_four_adjacent_pairs() {
+        let board = Board::from_fen("8/8/8/8/3w4/2w1w3/3w4/8").unwrap();
+        let index = |x, y| Index::new(x, y, Player::White);
 
-        // Check if source tile is not empty
-        let player = match get_tile_owner(source_tile) {
-            Some(player) => player,
-            None => return Err("Source tile is empty"),
-        };
-        let enemy_player = get_enemy(player);
+        assert_eq!(
+            board.get_adjacent_piece_pairs(Player::White),
+            vec![
+                (index(3, 4), index(2, 5)),
+                (index(3, 4), index(4, 5)),
+                (index(2, 5), index(3, 6)),
+                (index(4, 5), index(3, 6)),
+            ]
+        );
+        assert!(board.get_adjacent_piece_pairs(Player::Black).is_empty());
+    }
 
-        // Get target tile index
-        let target = match direction {
-            Direction::NW => source.translate(-2, -2),
-            Direction::NE => source.translate(2, -2),
-            Direction::SE => source.translate(2, 2),
-            Direction::SW => source.translate(-2, 2),
-        };
+    #[test]
+    fn same_board_with_other_player_to_move() {
+        let white_to_move = Game::from_fen("6/6/6/6/3b2/W5 w").unwrap();
+        let black_to_move = Game::from_fen("6/6/6/6/3b2/W5 b").unwrap();
 
-        // Check if we could translate index
-        let target_index = match target {
-            None => return Err("Target tile is outside of the board"),
-            Some(index) => index,
-        };
+        assert!(white_to_move.same_position_ignoring_turn(&black_to_move));
+        assert!(!white_to_move.same_position(&black_to_move));
+        assert!(white_to_move.same_position(&white_to_move.clone()));
+    }
 
-        // Check if target is on the board
-        let target_tile = match self.board.get_tile(target_index) {
-            Ok(tile) => tile,
-            Err(msg) => return Err(msg),
-        };
+    #[test]
+    fn starting_position_suggests_the_first_opening() {
+        let mut game = Game::new();
+        let (_, suggested_move, name) = OPENINGS[0];
 
-        // Check if target tile is empty
-        if target_tile != Tile::Empty {
-            return Err("Target tile is not empty");
-        }
+        assert_eq!(
+            game.suggest_opening(),
+            Some(format!(
+                "This is the {} opening. Recommended: {}",
+                name, suggested_move
+            ))
+        );
 
-        // Get captured tile index
-        let captured_index = match direction {
-            Direction::NW => source.translate(-1, -1),
-            Direction::NE => source.translate(1, -1),
-            Direction::SE => source.translate(1, 1),
-            Direction::SW => source.translate(-1, 1),
-        }
-        // Captured tile is between target and source, so it has to be on the board
-        .unwrap();
+        game.make_move(suggested_move).unwrap();
+        assert_eq!(
+            game.suggest_opening(),
+            Some(String::from(
+                "This is the Center opening. Recommended: E4 F5"
+            ))
+        );
 
-        // Captured tile is between target and source, so it has to be on the board
-        let captured_tile = self.board.get_tile(captured_index).unwrap();
+        assert_eq!(
+            Game::from_fen("6/6/6/6/3b2/W5 w")
+                .unwrap()
+                .suggest_opening(),
+            None
+        );
+    }
 
-        // Check if captured tile belongs to enemy
-        if get_tile_owner(captured_tile) != Some(enemy_player) {
-            return Err("Tile to be captured does not belong to enemy");
+    #[test]
+    fn apply_line_makes_every_move() {
+        let mut game = Game::new();
+
+        game.apply_line("D7 E6 e4 d5 B7 A6").unwrap();
+        assert_eq!(
+            game.to_fen(),
+            Game::from_move_list(&["D7 E6", "E4 D5", "B7 A6"], RulesConfig::default())
+                .unwrap()
+                .to_fen()
+        );
+
+        match game.apply_line("C4 B5 A6 A5") {
+            Err(CheckersError::InvalidMove { move_number, .. }) => assert_eq!(move_number, 2),
+            other => panic!("Expected invalid second move, got {:?}", other),
         }
+        assert_eq!(game.half_move_number(), 4);
 
-        Ok(AvailableMove::new(
-            source,
-            MoveType::Capture {
-                target_index,
-                captured_index,
-            },
-        ))
+        match game.apply_line("A6") {
+            Err(CheckersError::InvalidMove { move_number, .. }) => assert_eq!(move_number, 1),
+            other => panic!("Expected missing target, got {:?}", other),
+        }
     }
 
-    pub fn draw_board(&self) {
-        let board = self.board.get_drawed_board();
+    #[test]
+    fn fen_sequence_has_every_position() {
+        let game =
+            Game::from_move_list(&["D7 E6", "E4 D5", "B7 A6"], RulesConfig::default()).unwrap();
+        let fens = game.export_to_fen_sequence();
+
+        assert_eq!(fens.len(), game.half_move_number() + 1);
+        assert_eq!(fens[0], Game::new().to_fen());
+        assert_eq!(fens.last(), Some(&game.to_fen()));
+        assert!(fens[1].ends_with(" b"));
+        assert_eq!(game.half_move_number(), 3);
+    }
 
-        println!("{}", board);
+    #[test]
+    fn result_string_of_every_state() {
+        let mut game = Game::new();
+        assert_eq!(game.result_string(), "*");
+
+        for (state, result) in &[
+            (GameState::Turn(Player::Black), "*"),
+            (GameState::Won(Player::White), "1-0"),
+            (GameState::Won(Player::Black), "0-1"),
+            (GameState::Draw(DrawReason::ThreefoldRepetition), "1/2-1/2"),
+            (GameState::Draw(DrawReason::NoMoves), "1/2-1/2"),
+        ] {
+            game.state = *state;
+            assert_eq!(game.result_string(), *result);
+        }
     }
 
-    fn reverse_move(&self, game_move: &Move) -> Move {
-        let source = self.board.reverse_index(&game_move.source);
-        let target = self.board.reverse_index(&game_move.target);
+    #[test]
+    fn tiny_standard_boards_are_rejected() {
+        assert_eq!(
+            Board::try_new(2, 2),
+            Err(CheckersError::InvalidBoardSize {
+                height: 2,
+                width: 2
+            })
+        );
+        assert!(Board::try_new(4, 3).is_err());
+        assert!(Board::try_new(5, 8).is_err());
+        assert!(Board::try_new(4, 4).is_ok());
 
-        Move::new(source, target)
+        // empty boards have no setup to fit
+        assert_eq!(Board::new_empty(2, 2).material_array()[0], 4);
     }
 
-    pub fn draw_info(&self) {
-        println!("Note that your terminal may change pawn and board apperance, here is reference:");
+    #[test]
+    #[should_panic(expected = "Invalid board size")]
+    fn new_panics_on_tiny_boards() {
+        Board::new(2, 2);
+    }
 
-        self.board.draw_info();
+    #[test]
+    fn forced_promotion_rejects_other_moves() {
+        let fen = "8/2w5/8/8/3b4/8/5w2/8";
+        let quiet_move = Move::new(
+            Index::new(5, 6, Player::White),
+            Index::new(4, 5, Player::White),
+        );
+        let promotion = Move::new(
+            Index::new(2, 1, Player::White),
+            Index::new(1, 0, Player::White),
+        );
 
-        println!();
+        let game = game_with_rules(fen, RulesConfig::default());
+        assert!(!game.promotion_forced());
+        assert!(game.is_legal(quiet_move.clone()));
+
+        let game = game_with_rules(
+            fen,
+            RulesConfig {
+                forced_promotion: true,
+                ..RulesConfig::default()
+            },
+        );
+        assert!(game.promotion_forced());
+        assert!(!game.is_legal(quiet_move));
+        assert!(game.is_legal(promotion));
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-struct Move {
-    // Source tile index
-    source: Index,
-    // Target tile index
-    target: Index,
-}
+    #[test]
+    fn large_render_height_matches_square_size() {
+        let board = Board::new(8, 8);
+        let ansi = Regex::new("\x1b\\[[0-9;]*m").unwrap();
 
-impl Move {
-    pub fn new(source: Index, target: Index) -> Move {
-        Move { source, target }
+        for (square_height, square_width) in &[(3, 5), (1, 1), (2, 3)] {
+            let large = board.render_as_large(*square_height, *square_width);
+            let large = ansi.replace_all(&large, "");
+
+            assert_eq!(large.lines().count(), square_height * 8 + 8 + 1);
+            for line in large.lines() {
+                assert_eq!(line.chars().count(), square_width * 8 + 8 + 1);
+            }
+        }
     }
-}
 
-// Change to isize to enable simpler Index math
-#[derive(Debug, Copy, Clone, PartialEq)]
-struct Index {
-    // Player board orientation that the move is indexed from
-    orientation: Player,
-    // Horizontal index, 0-based
-    x: usize,
-    // Horizontal index, 0-based
-    y: usize,
-}
+    #[test]
+    fn opening_diagram_is_plain_ascii() {
+        let expected = concat!(
+            "  A B C D E F G H\n",
+            "1   b   b   b   b\n",
+            "2 b   b   b   b\n",
+            "3   b   b   b   b\n",
+            "4 .   .   .   .\n",
+            "5   .   .   .   .\n",
+            "6 w   w   w   w\n",
+            "7   w   w   w   w\n",
+            "8 w   w   w   w\n",
+        );
 
-impl Index {
-    /// Create new index, x is horizontal index, y is vertical. Indeces are 0-based
-    pub fn new(x: usize, y: usize, orientation: Player) -> Index {
-        Index { x, y, orientation }
+        assert_eq!(Board::new(8, 8).to_diagram(), expected);
+        assert!(Board::new(10, 10).to_diagram().starts_with("   A B C"));
     }
 
-    /// Creates new index moved by (x, y)
-    // change `Option` to `Result`
-    pub fn translate(&self, x: isize, y: isize) -> Option<Index> {
-        let x_new = usize::try_from((self.x as isize) + x);
-        let y_new = usize::try_from((self.y as isize) + y);
+    /// Makes a move in a child process of `trace_logs_moves_to_stderr`.
+    #[test]
+    #[ignore]
+    fn trace_child_move() {
+        Game::new().make_move("D7 E6").unwrap();
+    }
 
-        if x_new.is_err() || y_new.is_err() {
-            return None;
+    #[test]
+    fn trace_logs_moves_to_stderr() {
+        let run_child = |trace: bool| {
+            let mut command = process::Command::new(env::current_exe().unwrap());
+            command.args([
+                "tests::trace_child_move",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+            ]);
+            if trace {
+                command.env(TRACE_ENV_VAR, "1");
+            } else {
+                command.env_remove(TRACE_ENV_VAR);
+            }
+
+            let output = command.output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stderr).unwrap()
+        };
+
+        let traced = run_child(true);
+        if cfg!(debug_assertions) {
+            assert!(traced.contains("[trace] check_move("));
+            assert!(traced.contains("[trace] get_tile("));
+            assert!(traced.contains("[trace] translate("));
+        } else {
+            assert!(!traced.contains("[trace]"));
         }
+        assert!(!run_child(false).contains("[trace]"));
+    }
 
-        let index_translated = Index::new(x_new.unwrap(), y_new.unwrap(), self.orientation);
+    #[test]
+    fn capture_ordering_changes_only_the_order() {
+        let fen = "1b6/8/5b2/8/3b4/4w3/1b6/w7";
+        let by_source = game_with_rules(fen, RulesConfig::default()).legal_moves();
+        let by_capture_count = game_with_rules(
+            fen,
+            RulesConfig {
+                capture_ordering: CaptureOrdering::ByCaptureCount,
+                ..RulesConfig::default()
+            },
+        )
+        .legal_moves();
+
+        assert_ne!(by_source, by_capture_count);
+        assert_eq!(by_capture_count[0].capture_count(), 3);
+        assert!(by_capture_count
+            .windows(2)
+            .all(|pair| pair[0].capture_count() >= pair[1].capture_count()));
+
+        let mut sorted_by_source = by_source.clone();
+        let mut sorted_by_capture_count = by_capture_count.clone();
+        sorted_by_source.sort_by_key(|m| (m.source(), m.target()));
+        sorted_by_capture_count.sort_by_key(|m| (m.source(), m.target()));
+        assert_eq!(sorted_by_source, sorted_by_capture_count);
+    }
 
-        println!("Index translated: {:?}", index_translated);
+    #[test]
+    fn rotating_for_black_twice_restores_the_board() {
+        let board = Board::from_fen("1b1B2/6/6/6/1w1W2/w5").unwrap();
 
-        Some(index_translated)
+        assert_eq!(board.rotated_for(Player::White), board);
+        assert_eq!(
+            board.rotated_for(Player::Black).rotated_for(Player::Black),
+            board
+        );
+        assert_eq!(board.rotated_for(Player::Black), board.rotate_180());
+        assert_eq!(board.rotated_for(Player::Black).get(4, 5), Ok(Tile::Black));
     }
-}
 
-// TODO: move description doesn't support multi captures yet
-#[derive(Debug, Clone)]
-enum MoveType {
-    Move(Index),
-    Capture {
-        target_index: Index,
-        captured_index: Index,
-    },
-    MultiCapture(Vec<Index>),
-    KingMove(Index),
-    KingCapture {
-        target_index: Index,
-        captured_index: Index,
-    },
-    KingMultiCapture(Vec<Index>),
-}
+    #[test]
+    fn lone_king_against_lone_king_is_a_draw() {
+        let game = Game::from_fen("10/10/10/10/10/4B5/10/10/10/W9 w").unwrap();
+        assert!(game.is_theoretical_draw());
 
-#[derive(Debug, Clone)]
-struct AvailableMove {
-    source: Index,
-    move_type: MoveType,
-}
+        // cornered king has to step next to the enemy king
+        let cornered = Game::from_fen("10/10/10/10/10/10/10/2B7/10/W9 w").unwrap();
+        assert!(!cornered.is_theoretical_draw());
 
-impl AvailableMove {
-    pub fn new(source: Index, move_type: MoveType) -> AvailableMove {
-        AvailableMove { source, move_type }
+        let with_man = Game::from_fen("10/10/10/10/10/4B5/10/10/7w2/W9 w").unwrap();
+        assert!(!with_man.is_theoretical_draw());
     }
 
-    pub fn source(&self) -> Index {
-        self.source
-    }
+    #[test]
+    fn perft_of_every_variant() {
+        // pool rules forbid backward captures of men, which first happen at depth 4
+        for (rules, breakdown) in [
+            (
+                RulesConfig::default(),
+                vec![(1, 7), (2, 49), (3, 379), (4, 2900)],
+            ),
+            (
+                RulesConfig::pool(),
+                vec![(1, 7), (2, 49), (3, 379), (4, 2872)],
+            ),
+        ] {
+            assert_eq!(Game::with_rules(rules).perft_breakdown(1), vec![(1, 9)]);
 
-    pub fn move_type(&self) -> &MoveType {
-        &self.move_type
+            let game = Game::with_board(Board::new(8, 8), rules);
+            assert_eq!(game.perft_breakdown(4), breakdown);
+            assert_eq!(perft_by_successors(&game, 4), breakdown[3].1);
+        }
     }
 }